@@ -0,0 +1,20 @@
+(* Copyright (c) SimpleStaking and Tezedge Contributors
+   SPDX-License-Identifier: MIT *)
+
+(* Lwt support code for the ocaml-interop Rust library. Must be linked with
+   [-linkall] so that the registrations are performed. *)
+
+let wait () = Lwt.wait ()
+
+let on_any promise on_resolved on_rejected =
+  Lwt.on_any promise on_resolved on_rejected
+
+let resolve resolver value = Lwt.wakeup_later resolver value
+
+let reject resolver message = Lwt.wakeup_later_exn resolver (Failure message)
+
+let () =
+  Callback.register "ocaml_interop_lwt_wait" wait;
+  Callback.register "ocaml_interop_lwt_on_any" on_any;
+  Callback.register "ocaml_interop_lwt_resolve" resolve;
+  Callback.register "ocaml_interop_lwt_reject" reject
@@ -0,0 +1,34 @@
+(* Copyright (c) SimpleStaking and Tezedge Contributors
+   SPDX-License-Identifier: MIT *)
+
+(* Support code for the ocaml-interop Rust library. The registered values are
+   looked up from Rust with [caml_named_value]. Programs that make use of them
+   must link this library with [-linkall] so that the registrations are performed. *)
+
+type rust_closure
+
+external call_rust_closure : rust_closure -> 'a -> 'b
+  = "ocaml_interop_call_rust_closure"
+
+let make_closure (closure : rust_closure) x = call_rust_closure closure x
+
+let () = Callback.register "ocaml_interop_make_closure" make_closure
+
+let () =
+  Callback.register "ocaml_interop_gc_finalise" Gc.finalise;
+  Callback.register "ocaml_interop_gc_finalise_last" Gc.finalise_last
+
+let hashtbl_of_list bindings =
+  let table = Hashtbl.create (List.length bindings) in
+  List.iter (fun (key, value) -> Hashtbl.replace table key value) bindings;
+  table
+
+let () = Callback.register "ocaml_interop_hashtbl_of_list" hashtbl_of_list
+
+let () =
+  Callback.register "ocaml_interop_lazy_force" (fun l -> Lazy.force l);
+  Callback.register "ocaml_interop_lazy_from_fun" Lazy.from_fun
+
+let () =
+  Callback.register "ocaml_interop_compare" (fun a b -> compare a b);
+  Callback.register "ocaml_interop_equal" (fun a b -> a = b)
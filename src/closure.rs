@@ -1,11 +1,16 @@
 // Copyright (c) SimpleStaking and Tezedge Contributors
 // SPDX-License-Identifier: MIT
 
-use crate::error::OCamlException;
+use crate::error::{OCamlException, OCamlFunctionNotRegistered};
+use crate::internal::named_value;
+use crate::memory::{alloc_box, box_value_ref, OCamlCell};
 use crate::mlvalues::tag;
-use crate::mlvalues::{extract_exception, is_exception_result, tag_val, RawOCaml};
+use crate::mlvalues::{
+    extract_exception, is_exception_result, tag_val, DynBox, OCamlFunction, RawOCaml,
+};
 use crate::value::OCaml;
-use crate::{OCamlRef, OCamlRuntime};
+use crate::{ocaml_frame, OCamlRef, OCamlRuntime};
+use core::sync::atomic::{AtomicPtr, Ordering};
 use ocaml_sys::{
     caml_callback2_exn, caml_callback3_exn, caml_callbackN_exn, caml_callback_exn, caml_named_value,
 };
@@ -16,6 +21,11 @@ pub struct OCamlClosure(*const RawOCaml);
 unsafe impl Sync for OCamlClosure {}
 
 impl OCamlClosure {
+    // `closure` must point to a closure value that stays valid until the call is performed
+    pub(crate) unsafe fn of_ptr(closure: *const RawOCaml) -> OCamlClosure {
+        OCamlClosure(closure)
+    }
+
     pub fn named(name: &str) -> Option<OCamlClosure> {
         let named = unsafe {
             let s = match std::ffi::CString::new(name) {
@@ -36,6 +46,21 @@ impl OCamlClosure {
         self.handle_call_result(cr, result)
     }
 
+    /// Like `call`, but returns the exception raised by the closure as an error instead of
+    /// panicking.
+    pub fn try_call<'a, T, R>(
+        &self,
+        cr: &'a mut OCamlRuntime,
+        arg: OCamlRef<T>,
+    ) -> Result<OCaml<'a, R>, OCamlException> {
+        let result = unsafe { caml_callback_exn(*self.0, arg.get_raw()) };
+        if is_exception_result(result) {
+            Err(unsafe { OCamlException::of(extract_exception(result)) })
+        } else {
+            Ok(unsafe { OCaml::new(cr, result) })
+        }
+    }
+
     pub fn call2<'a, T, U, R>(
         &self,
         cr: &'a mut OCamlRuntime,
@@ -46,6 +71,22 @@ impl OCamlClosure {
         self.handle_call_result(cr, result)
     }
 
+    /// Like `call2`, but returns the exception raised by the closure as an error instead of
+    /// panicking.
+    pub fn try_call2<'a, T, U, R>(
+        &self,
+        cr: &'a mut OCamlRuntime,
+        arg1: OCamlRef<T>,
+        arg2: OCamlRef<U>,
+    ) -> Result<OCaml<'a, R>, OCamlException> {
+        let result = unsafe { caml_callback2_exn(*self.0, arg1.get_raw(), arg2.get_raw()) };
+        if is_exception_result(result) {
+            Err(unsafe { OCamlException::of(extract_exception(result)) })
+        } else {
+            Ok(unsafe { OCaml::new(cr, result) })
+        }
+    }
+
     pub fn call3<'a, T, U, V, R>(
         &self,
         cr: &'a mut OCamlRuntime,
@@ -79,6 +120,129 @@ impl OCamlClosure {
     }
 }
 
+// Location of a closure registered with `Callback.register`, looked up again on every
+// request until it is found, because OCaml may register it after the first call.
+pub struct OCamlClosureSlot {
+    slot: OCamlNamedSlot,
+}
+
+impl OCamlClosureSlot {
+    pub const fn new(name: &'static str) -> Self {
+        OCamlClosureSlot {
+            slot: OCamlNamedSlot::new(name),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.slot.name()
+    }
+
+    pub fn resolve(&self) -> Result<OCamlClosure, OCamlFunctionNotRegistered> {
+        match self.slot.get() {
+            Some(closure) if unsafe { tag_val(*closure) } == tag::CLOSURE => {
+                Ok(OCamlClosure(closure))
+            }
+            _ => Err(OCamlFunctionNotRegistered::new(self.slot.name())),
+        }
+    }
+}
+
+// Location of a value registered with `Callback.register`, that is looked up
+// again on every access until it has been registered. Registering a value again
+// under the same name updates it in place, so found locations are cached.
+pub struct OCamlNamedSlot {
+    name: &'static str,
+    location: AtomicPtr<RawOCaml>,
+}
+
+impl OCamlNamedSlot {
+    pub const fn new(name: &'static str) -> Self {
+        OCamlNamedSlot {
+            name,
+            location: AtomicPtr::new(core::ptr::null_mut()),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    pub fn get(&self) -> Option<*const RawOCaml> {
+        let location = self.location.load(Ordering::Acquire);
+        if !location.is_null() {
+            return Some(location);
+        }
+        let location = named_value(self.name);
+        if location.is_null() {
+            None
+        } else {
+            self.location
+                .store(location as *mut RawOCaml, Ordering::Release);
+            Some(location)
+        }
+    }
+}
+
+impl<A, Ret> OCamlCell<OCamlFunction<A, Ret>> {
+    /// Applies the OCaml function to `arg`.
+    ///
+    /// Calls that raise an OCaml exception will `panic!`.
+    pub fn call<'a>(&self, cr: &'a mut OCamlRuntime, arg: OCamlRef<A>) -> OCaml<'a, Ret> {
+        OCamlClosure(self as *const Self as *const RawOCaml).call(cr, arg)
+    }
+}
+
+// Rust closures are called from OCaml through a closure created by the
+// `ocaml_interop` OCaml support library, that applies the
+// `ocaml_interop_call_rust_closure` primitive to the boxed Rust closure and the argument.
+
+type BoxedRustClosure = Box<dyn Fn(&mut OCamlRuntime, RawOCaml) -> RawOCaml>;
+
+#[doc(hidden)]
+#[no_mangle]
+pub extern "C" fn ocaml_interop_call_rust_closure(closure: RawOCaml, arg: RawOCaml) -> RawOCaml {
+    let cr = unsafe { OCamlRuntime::recover_handle() };
+    ocaml_frame!(cr, (closure_root), {
+        // Rooted so that the closure is not collected while it is running
+        let closure: OCamlRef<DynBox<BoxedRustClosure>> = unsafe { closure_root.keep_raw(closure) };
+        let f: &BoxedRustClosure = unsafe { box_value_ref(closure.get_raw()) };
+        f(cr, arg)
+    })
+}
+
+impl<'a, A: 'static, Ret: 'static> OCaml<'a, OCamlFunction<A, Ret>> {
+    /// Allocates an OCaml function that calls the Rust closure `f` when applied.
+    ///
+    /// The closure is dropped when the OCaml function value is collected by the GC.
+    ///
+    /// # Note
+    ///
+    /// Requires the `ocaml_interop` OCaml library (in the `ocaml/` directory of this repository)
+    /// to be linked into the program.
+    ///
+    /// The closure is dropped by a finaliser while the GC is running, so the values it captures
+    /// have the same restrictions as values passed to [`OCaml::box_value`]: they must not call
+    /// into the OCaml runtime when dropped, and must not own an
+    /// [`OCamlGlobalRoot`](crate::OCamlGlobalRoot).
+    pub fn of_rust_closure<F>(cr: &'a mut OCamlRuntime, f: F) -> Self
+    where
+        F: for<'b> Fn(&'b mut OCamlRuntime, OCamlRef<'b, A>) -> OCaml<'b, Ret> + 'static,
+    {
+        let closure: BoxedRustClosure = Box::new(move |cr, arg| {
+            ocaml_frame!(cr, (arg_root), {
+                let arg = unsafe { arg_root.keep_raw(arg) };
+                unsafe { f(cr, arg).raw() }
+            })
+        });
+        crate::ocaml_closure_reference!(make_closure, ocaml_interop_make_closure);
+        ocaml_frame!(cr, (closure_root), {
+            let closure = alloc_box(cr, closure);
+            let closure = closure_root.keep(closure);
+            make_closure.call(cr, closure)
+        })
+    }
+}
+
 /// OCaml function that accepts one argument.
 pub type OCamlFn1<'a, A, Ret> = unsafe fn(&'a mut OCamlRuntime, OCamlRef<A>) -> OCaml<'a, Ret>;
 /// OCaml function that accepts two arguments.
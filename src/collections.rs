@@ -0,0 +1,510 @@
+// Copyright (c) SimpleStaking and Tezedge Contributors
+// SPDX-License-Identifier: MIT
+
+use crate::{
+    closure::OCamlClosure,
+    conv::{FromOCaml, ToOCaml},
+    memory::{alloc_cons, alloc_tuple, OCamlRef},
+    mlvalues::{wosize_val, OCamlHashtbl, OCamlInt, OCamlList, OCamlMap, OCamlSet, UIntnat},
+    ocaml_closure_reference, ocaml_frame,
+    runtime::OCamlRuntime,
+    to_ocaml,
+    value::OCaml,
+};
+use core::{hash::Hash, marker::PhantomData};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    hash::BuildHasher,
+};
+
+// `Hashtbl.t` is a record with the number of bindings and an array of buckets:
+//   { mutable size: int; mutable data: ('a, 'b) bucketlist array; seed: int; mutable initial_size: int }
+// Each bucket is either `Empty` or `Cons of { mutable key: 'a; mutable data: 'b; mutable next: ('a, 'b) bucketlist }`.
+const HASHTBL_SIZE: UIntnat = 0;
+const HASHTBL_DATA: UIntnat = 1;
+const BUCKET_KEY: UIntnat = 0;
+const BUCKET_DATA: UIntnat = 1;
+const BUCKET_NEXT: UIntnat = 2;
+
+// Maps and sets are AVL trees, either `Empty` or:
+//   Map: Node of { l: 'a t; v: key; d: 'a; r: 'a t; h: int }
+//   Set: Node of { l: t; v: elt; r: t; h: int }
+const NODE_LEFT: UIntnat = 0;
+const NODE_VALUE: UIntnat = 1;
+const MAP_NODE_DATA: UIntnat = 2;
+const MAP_NODE_RIGHT: UIntnat = 3;
+const SET_NODE_RIGHT: UIntnat = 2;
+
+// `OCaml<OCamlBuckets<K, V>>` is a reference to the buckets array of a `Hashtbl.t`.
+struct OCamlBuckets<K, V> {
+    _marker: PhantomData<(K, V)>,
+}
+
+// `OCaml<OCamlBucket<K, V>>` is a reference to a bucket of a `Hashtbl.t`.
+struct OCamlBucket<K, V> {
+    _marker: PhantomData<(K, V)>,
+}
+
+impl<'a, K, V> OCaml<'a, OCamlHashtbl<K, V>> {
+    /// Returns the number of bindings in the table.
+    pub fn len(&self) -> usize {
+        unsafe { self.field::<OCamlInt>(HASHTBL_SIZE) }.to_i64() as usize
+    }
+
+    /// Returns true if the table has no bindings.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns an iterator over the bindings in the table.
+    ///
+    /// When a key has more than one binding (added with `Hashtbl.add`), the current
+    /// binding is returned before the ones it hides.
+    pub fn iter(&self) -> OCamlHashtblIter<'a, K, V> {
+        let buckets: OCaml<'a, OCamlBuckets<K, V>> = unsafe { self.field(HASHTBL_DATA) };
+        OCamlHashtblIter {
+            len: unsafe { wosize_val(buckets.raw()) },
+            buckets,
+            index: 0,
+            bucket: None,
+        }
+    }
+}
+
+/// Iterator over the bindings of an OCaml `Hashtbl.t`, returned by
+/// `OCaml::<OCamlHashtbl<K, V>>::iter`.
+pub struct OCamlHashtblIter<'a, K, V> {
+    buckets: OCaml<'a, OCamlBuckets<K, V>>,
+    len: UIntnat,
+    index: UIntnat,
+    bucket: Option<OCaml<'a, OCamlBucket<K, V>>>,
+}
+
+impl<'a, K, V> Iterator for OCamlHashtblIter<'a, K, V> {
+    type Item = (OCaml<'a, K>, OCaml<'a, V>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(bucket) = self.bucket.take() {
+                if bucket.is_block() {
+                    self.bucket = Some(unsafe { bucket.field(BUCKET_NEXT) });
+                    return Some(unsafe { (bucket.field(BUCKET_KEY), bucket.field(BUCKET_DATA)) });
+                }
+            }
+            if self.index == self.len {
+                return None;
+            }
+            self.bucket = Some(unsafe { self.buckets.field(self.index) });
+            self.index += 1;
+        }
+    }
+}
+
+// In-order traversal of the nodes of the AVL tree of a map or a set.
+struct AvlNodes<'a, T> {
+    stack: Vec<OCaml<'a, T>>,
+    right: UIntnat,
+}
+
+impl<'a, T> AvlNodes<'a, T> {
+    fn new(root: OCaml<'a, T>, right: UIntnat) -> Self {
+        let mut nodes = AvlNodes {
+            stack: Vec::new(),
+            right,
+        };
+        nodes.push_left_spine(root);
+        nodes
+    }
+
+    fn push_left_spine(&mut self, mut node: OCaml<'a, T>) {
+        while node.is_block() {
+            let left = unsafe { node.field(NODE_LEFT) };
+            self.stack.push(node);
+            node = left;
+        }
+    }
+}
+
+impl<'a, T> Iterator for AvlNodes<'a, T> {
+    type Item = OCaml<'a, T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.stack.pop()?;
+        self.push_left_spine(unsafe { node.field(self.right) });
+        Some(node)
+    }
+}
+
+impl<'a, K, V> OCaml<'a, OCamlMap<K, V>> {
+    /// Returns true if the map has no bindings.
+    pub fn is_empty(&self) -> bool {
+        self.is_long()
+    }
+
+    /// Returns an iterator over the bindings of the map, in increasing order of keys.
+    pub fn iter(&self) -> OCamlMapIter<'a, K, V> {
+        OCamlMapIter {
+            nodes: AvlNodes::new(*self, MAP_NODE_RIGHT),
+        }
+    }
+}
+
+impl<K, V> OCamlMap<K, V> {
+    /// Builds a map from a list of bindings by calling `of_list`, an OCaml function of type
+    /// `(key * 'a) list -> 'a t` registered with `Callback.register` (for example
+    /// `Callback.register "string_map_of_list" (fun l -> StringMap.of_seq (List.to_seq l))`).
+    ///
+    /// # Panics
+    ///
+    /// Panics if no function named `of_list` has been registered, or if it raises an exception.
+    pub fn of_list<'a>(
+        cr: &'a mut OCamlRuntime,
+        of_list: &str,
+        bindings: OCamlRef<OCamlList<(K, V)>>,
+    ) -> OCaml<'a, OCamlMap<K, V>> {
+        registered_of_list(of_list).call(cr, bindings)
+    }
+}
+
+/// Iterator over the bindings of an OCaml map, returned by `OCaml::<OCamlMap<K, V>>::iter`.
+pub struct OCamlMapIter<'a, K, V> {
+    nodes: AvlNodes<'a, OCamlMap<K, V>>,
+}
+
+impl<'a, K, V> Iterator for OCamlMapIter<'a, K, V> {
+    type Item = (OCaml<'a, K>, OCaml<'a, V>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.nodes.next()?;
+        Some(unsafe { (node.field(NODE_VALUE), node.field(MAP_NODE_DATA)) })
+    }
+}
+
+impl<'a, A> OCaml<'a, OCamlSet<A>> {
+    /// Returns true if the set has no elements.
+    pub fn is_empty(&self) -> bool {
+        self.is_long()
+    }
+
+    /// Returns an iterator over the elements of the set, in increasing order.
+    pub fn iter(&self) -> OCamlSetIter<'a, A> {
+        OCamlSetIter {
+            nodes: AvlNodes::new(*self, SET_NODE_RIGHT),
+        }
+    }
+}
+
+impl<A> OCamlSet<A> {
+    /// Builds a set from a list of elements by calling `of_list`, an OCaml function of type
+    /// `elt list -> t` registered with `Callback.register` (for example
+    /// `Callback.register "string_set_of_list" StringSet.of_list`).
+    ///
+    /// # Panics
+    ///
+    /// Panics if no function named `of_list` has been registered, or if it raises an exception.
+    pub fn of_list<'a>(
+        cr: &'a mut OCamlRuntime,
+        of_list: &str,
+        elements: OCamlRef<OCamlList<A>>,
+    ) -> OCaml<'a, OCamlSet<A>> {
+        registered_of_list(of_list).call(cr, elements)
+    }
+}
+
+/// Iterator over the elements of an OCaml set, returned by `OCaml::<OCamlSet<T>>::iter`.
+pub struct OCamlSetIter<'a, A> {
+    nodes: AvlNodes<'a, OCamlSet<A>>,
+}
+
+impl<'a, A> Iterator for OCamlSetIter<'a, A> {
+    type Item = OCaml<'a, A>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.nodes.next()?;
+        Some(unsafe { node.field(NODE_VALUE) })
+    }
+}
+
+fn registered_of_list(name: &str) -> OCamlClosure {
+    OCamlClosure::named(name)
+        .unwrap_or_else(|| panic!("OCaml closure with name '{}' not registered", name))
+}
+
+// Builds an association list, the bindings end up in the reverse order of the iterator.
+fn alloc_assoc_list<'a, 'b, K, V, OCamlK, OCamlV>(
+    cr: &'a mut OCamlRuntime,
+    bindings: impl Iterator<Item = (&'b K, &'b V)>,
+) -> OCaml<'a, OCamlList<(OCamlK, OCamlV)>>
+where
+    K: ToOCaml<OCamlK> + 'b,
+    V: ToOCaml<OCamlV> + 'b,
+{
+    ocaml_frame!(cr, (result_root, key_root, value_root, binding_root), {
+        let mut result = result_root.keep(OCaml::nil());
+        for (key, value) in bindings {
+            let key = to_ocaml!(cr, key, key_root);
+            let value = to_ocaml!(cr, value, value_root);
+            let binding = alloc_tuple(cr, key, value);
+            let binding = binding_root.keep(binding);
+            let cons = alloc_cons(cr, binding, result);
+            result = result_root.keep(cons);
+        }
+        cr.get(result)
+    })
+}
+
+// Builds a list, the elements end up in the reverse order of the iterator.
+fn alloc_list<'a, 'b, A, OCamlA>(
+    cr: &'a mut OCamlRuntime,
+    elements: impl Iterator<Item = &'b A>,
+) -> OCaml<'a, OCamlList<OCamlA>>
+where
+    A: ToOCaml<OCamlA> + 'b,
+{
+    ocaml_frame!(cr, (result_root, elt_root), {
+        let mut result = result_root.keep(OCaml::nil());
+        for elt in elements {
+            let elt = to_ocaml!(cr, elt, elt_root);
+            let cons = alloc_cons(cr, elt, result);
+            result = result_root.keep(cons);
+        }
+        cr.get(result)
+    })
+}
+
+fn alloc_hashtbl<'a, 'b, K, V, OCamlK, OCamlV>(
+    cr: &'a mut OCamlRuntime,
+    bindings: impl Iterator<Item = (&'b K, &'b V)>,
+) -> OCaml<'a, OCamlHashtbl<OCamlK, OCamlV>>
+where
+    K: ToOCaml<OCamlK> + 'b,
+    V: ToOCaml<OCamlV> + 'b,
+{
+    ocaml_closure_reference!(hashtbl_of_list, ocaml_interop_hashtbl_of_list);
+    ocaml_frame!(cr, (bindings_root), {
+        let bindings = alloc_assoc_list(cr, bindings);
+        let bindings = bindings_root.keep(bindings);
+        hashtbl_of_list.call(cr, bindings)
+    })
+}
+
+// Association lists
+
+unsafe impl<K, V, S, OCamlK, OCamlV> ToOCaml<OCamlList<(OCamlK, OCamlV)>> for HashMap<K, V, S>
+where
+    K: ToOCaml<OCamlK>,
+    V: ToOCaml<OCamlV>,
+{
+    fn to_ocaml<'a>(&self, cr: &'a mut OCamlRuntime) -> OCaml<'a, OCamlList<(OCamlK, OCamlV)>> {
+        alloc_assoc_list(cr, self.iter())
+    }
+}
+
+unsafe impl<K, V, OCamlK, OCamlV> ToOCaml<OCamlList<(OCamlK, OCamlV)>> for BTreeMap<K, V>
+where
+    K: ToOCaml<OCamlK>,
+    V: ToOCaml<OCamlV>,
+{
+    fn to_ocaml<'a>(&self, cr: &'a mut OCamlRuntime) -> OCaml<'a, OCamlList<(OCamlK, OCamlV)>> {
+        alloc_assoc_list(cr, self.iter().rev())
+    }
+}
+
+unsafe impl<A, S, OCamlA> ToOCaml<OCamlList<OCamlA>> for HashSet<A, S>
+where
+    A: ToOCaml<OCamlA>,
+{
+    fn to_ocaml<'a>(&self, cr: &'a mut OCamlRuntime) -> OCaml<'a, OCamlList<OCamlA>> {
+        alloc_list(cr, self.iter())
+    }
+}
+
+unsafe impl<A, OCamlA> ToOCaml<OCamlList<OCamlA>> for BTreeSet<A>
+where
+    A: ToOCaml<OCamlA>,
+{
+    fn to_ocaml<'a>(&self, cr: &'a mut OCamlRuntime) -> OCaml<'a, OCamlList<OCamlA>> {
+        alloc_list(cr, self.iter().rev())
+    }
+}
+
+// Like `List.assoc`, when a key is bound more than once the first binding is kept.
+
+unsafe impl<K, V, S, OCamlK, OCamlV> FromOCaml<OCamlList<(OCamlK, OCamlV)>> for HashMap<K, V, S>
+where
+    K: FromOCaml<OCamlK> + Eq + Hash,
+    V: FromOCaml<OCamlV>,
+    S: BuildHasher + Default,
+{
+    fn from_ocaml(v: OCaml<OCamlList<(OCamlK, OCamlV)>>) -> Self {
+        let mut map = HashMap::default();
+        let mut current = v;
+        while let Some((binding, tl)) = current.uncons() {
+            current = tl;
+            map.entry(K::from_ocaml(binding.fst()))
+                .or_insert_with(|| V::from_ocaml(binding.snd()));
+        }
+        map
+    }
+}
+
+unsafe impl<K, V, OCamlK, OCamlV> FromOCaml<OCamlList<(OCamlK, OCamlV)>> for BTreeMap<K, V>
+where
+    K: FromOCaml<OCamlK> + Ord,
+    V: FromOCaml<OCamlV>,
+{
+    fn from_ocaml(v: OCaml<OCamlList<(OCamlK, OCamlV)>>) -> Self {
+        let mut map = BTreeMap::new();
+        let mut current = v;
+        while let Some((binding, tl)) = current.uncons() {
+            current = tl;
+            map.entry(K::from_ocaml(binding.fst()))
+                .or_insert_with(|| V::from_ocaml(binding.snd()));
+        }
+        map
+    }
+}
+
+unsafe impl<A, S, OCamlA> FromOCaml<OCamlList<OCamlA>> for HashSet<A, S>
+where
+    A: FromOCaml<OCamlA> + Eq + Hash,
+    S: BuildHasher + Default,
+{
+    fn from_ocaml(v: OCaml<OCamlList<OCamlA>>) -> Self {
+        let mut set = HashSet::default();
+        let mut current = v;
+        while let Some((hd, tl)) = current.uncons() {
+            current = tl;
+            set.insert(A::from_ocaml(hd));
+        }
+        set
+    }
+}
+
+unsafe impl<A, OCamlA> FromOCaml<OCamlList<OCamlA>> for BTreeSet<A>
+where
+    A: FromOCaml<OCamlA> + Ord,
+{
+    fn from_ocaml(v: OCaml<OCamlList<OCamlA>>) -> Self {
+        let mut set = BTreeSet::new();
+        let mut current = v;
+        while let Some((hd, tl)) = current.uncons() {
+            current = tl;
+            set.insert(A::from_ocaml(hd));
+        }
+        set
+    }
+}
+
+// Hashtbl.t
+
+/// Builds a hash table with `Hashtbl.replace`.
+///
+/// # Note
+///
+/// Requires the `ocaml_interop` OCaml library (in the `ocaml/` directory of this repository)
+/// to be linked into the program.
+unsafe impl<K, V, S, OCamlK, OCamlV> ToOCaml<OCamlHashtbl<OCamlK, OCamlV>> for HashMap<K, V, S>
+where
+    K: ToOCaml<OCamlK>,
+    V: ToOCaml<OCamlV>,
+{
+    fn to_ocaml<'a>(&self, cr: &'a mut OCamlRuntime) -> OCaml<'a, OCamlHashtbl<OCamlK, OCamlV>> {
+        alloc_hashtbl(cr, self.iter())
+    }
+}
+
+/// Builds a hash table with `Hashtbl.replace`.
+///
+/// # Note
+///
+/// Requires the `ocaml_interop` OCaml library (in the `ocaml/` directory of this repository)
+/// to be linked into the program.
+unsafe impl<K, V, OCamlK, OCamlV> ToOCaml<OCamlHashtbl<OCamlK, OCamlV>> for BTreeMap<K, V>
+where
+    K: ToOCaml<OCamlK>,
+    V: ToOCaml<OCamlV>,
+{
+    fn to_ocaml<'a>(&self, cr: &'a mut OCamlRuntime) -> OCaml<'a, OCamlHashtbl<OCamlK, OCamlV>> {
+        alloc_hashtbl(cr, self.iter())
+    }
+}
+
+// Hidden bindings (added with `Hashtbl.add`) come after the current one, and are ignored.
+
+unsafe impl<K, V, S, OCamlK, OCamlV> FromOCaml<OCamlHashtbl<OCamlK, OCamlV>> for HashMap<K, V, S>
+where
+    K: FromOCaml<OCamlK> + Eq + Hash,
+    V: FromOCaml<OCamlV>,
+    S: BuildHasher + Default,
+{
+    fn from_ocaml(v: OCaml<OCamlHashtbl<OCamlK, OCamlV>>) -> Self {
+        let mut map = HashMap::with_capacity_and_hasher(v.len(), S::default());
+        for (key, value) in v.iter() {
+            map.entry(K::from_ocaml(key))
+                .or_insert_with(|| V::from_ocaml(value));
+        }
+        map
+    }
+}
+
+unsafe impl<K, V, OCamlK, OCamlV> FromOCaml<OCamlHashtbl<OCamlK, OCamlV>> for BTreeMap<K, V>
+where
+    K: FromOCaml<OCamlK> + Ord,
+    V: FromOCaml<OCamlV>,
+{
+    fn from_ocaml(v: OCaml<OCamlHashtbl<OCamlK, OCamlV>>) -> Self {
+        let mut map = BTreeMap::new();
+        for (key, value) in v.iter() {
+            map.entry(K::from_ocaml(key))
+                .or_insert_with(|| V::from_ocaml(value));
+        }
+        map
+    }
+}
+
+// Map.Make and Set.Make
+
+unsafe impl<K, V, S, OCamlK, OCamlV> FromOCaml<OCamlMap<OCamlK, OCamlV>> for HashMap<K, V, S>
+where
+    K: FromOCaml<OCamlK> + Eq + Hash,
+    V: FromOCaml<OCamlV>,
+    S: BuildHasher + Default,
+{
+    fn from_ocaml(v: OCaml<OCamlMap<OCamlK, OCamlV>>) -> Self {
+        v.iter()
+            .map(|(key, value)| (K::from_ocaml(key), V::from_ocaml(value)))
+            .collect()
+    }
+}
+
+unsafe impl<K, V, OCamlK, OCamlV> FromOCaml<OCamlMap<OCamlK, OCamlV>> for BTreeMap<K, V>
+where
+    K: FromOCaml<OCamlK> + Ord,
+    V: FromOCaml<OCamlV>,
+{
+    fn from_ocaml(v: OCaml<OCamlMap<OCamlK, OCamlV>>) -> Self {
+        v.iter()
+            .map(|(key, value)| (K::from_ocaml(key), V::from_ocaml(value)))
+            .collect()
+    }
+}
+
+unsafe impl<A, S, OCamlA> FromOCaml<OCamlSet<OCamlA>> for HashSet<A, S>
+where
+    A: FromOCaml<OCamlA> + Eq + Hash,
+    S: BuildHasher + Default,
+{
+    fn from_ocaml(v: OCaml<OCamlSet<OCamlA>>) -> Self {
+        v.iter().map(A::from_ocaml).collect()
+    }
+}
+
+unsafe impl<A, OCamlA> FromOCaml<OCamlSet<OCamlA>> for BTreeSet<A>
+where
+    A: FromOCaml<OCamlA> + Ord,
+{
+    fn from_ocaml(v: OCaml<OCamlSet<OCamlA>>) -> Self {
+        v.iter().map(A::from_ocaml).collect()
+    }
+}
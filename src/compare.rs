@@ -0,0 +1,162 @@
+// Copyright (c) SimpleStaking and Tezedge Contributors
+// SPDX-License-Identifier: MIT
+
+use crate::{
+    error::OCamlException,
+    memory::{OCamlCell, OCamlGlobalRoot},
+    mlvalues::{OCamlInt, RawOCaml},
+    ocaml_closure_reference, OCaml, OCamlRef, OCamlRuntime,
+};
+use core::cmp::Ordering;
+use core::convert::TryFrom;
+use core::hash::{Hash, Hasher};
+use ocaml_sys::{int_val, val_int};
+
+extern "C" {
+    fn caml_hash(count: RawOCaml, limit: RawOCaml, seed: RawOCaml, obj: RawOCaml) -> RawOCaml;
+}
+
+impl<T> OCamlCell<T> {
+    /// Compares two OCaml values with OCaml's polymorphic `compare`.
+    ///
+    /// Comparing functional values raises `Invalid_argument`, which is returned as an error.
+    /// Like in OCaml, physically equal values are equal without being inspected, so comparing
+    /// a value with itself never fails.
+    ///
+    /// # Note
+    ///
+    /// This requires the `ocaml_interop` OCaml library (in the `ocaml/` directory of this
+    /// repository) to be linked into the program.
+    pub fn compare(
+        &self,
+        cr: &mut OCamlRuntime,
+        other: OCamlRef<T>,
+    ) -> Result<Ordering, OCamlException> {
+        ocaml_closure_reference!(compare, ocaml_interop_compare);
+        let result: OCaml<OCamlInt> = compare.try_call2(cr, self, other)?;
+        Ok(result.to_i64().cmp(&0))
+    }
+
+    /// Tests two OCaml values for structural equality with OCaml's polymorphic `=`.
+    ///
+    /// Comparing functional values raises `Invalid_argument`, which is returned as an error.
+    ///
+    /// # Note
+    ///
+    /// This requires the `ocaml_interop` OCaml library (in the `ocaml/` directory of this
+    /// repository) to be linked into the program.
+    pub fn equal(&self, cr: &mut OCamlRuntime, other: OCamlRef<T>) -> Result<bool, OCamlException> {
+        ocaml_closure_reference!(equal, ocaml_interop_equal);
+        let result: OCaml<bool> = equal.try_call2(cr, self, other)?;
+        Ok(result.to_bool())
+    }
+
+    /// Hashes an OCaml value like `Hashtbl.hash` does.
+    ///
+    /// Values that are equal according to `compare` have the same hash.
+    /// Functional values are not traversed, so hashing them never fails.
+    pub fn hash(&self, cr: &OCamlRuntime) -> i64 {
+        let value = unsafe { cr.get(self).raw() };
+        unsafe { int_val(caml_hash(val_int(10), val_int(100), val_int(0), value)) as i64 }
+    }
+}
+
+/// A global root that compares, tests for equality and hashes the OCaml value it keeps
+/// with OCaml's polymorphic `compare` and `Hashtbl.hash`.
+///
+/// This makes it possible to use OCaml values as keys of Rust collections like
+/// `BTreeMap` and `HashMap`. Equality follows `compare` instead of `=`, so that `nan`
+/// is equal to itself and the `Eq` and `Ord` implementations are consistent.
+///
+/// Like [`OCamlGlobalRoot`], it can only be used from the thread that is running the
+/// OCaml runtime, and the comparisons call into it.
+///
+/// # Panics
+///
+/// Keys are only created for values that can be compared, so comparisons only panic if a
+/// functional or abstract value is stored in a mutable part of a key after it has been created,
+/// or if keys are compared or hashed without holding the OCaml runtime.
+///
+/// # Note
+///
+/// Comparisons require the `ocaml_interop` OCaml library (in the `ocaml/` directory of this
+/// repository) to be linked into the program.
+pub struct OCamlKey<T> {
+    root: OCamlGlobalRoot<T>,
+}
+
+impl<T> OCamlKey<T> {
+    /// Creates a new key that keeps `val` alive.
+    ///
+    /// The value is tested for equality with itself once, to check that it can be compared.
+    /// Like in OCaml, this doesn't terminate for cyclic values.
+    ///
+    /// # Errors
+    ///
+    /// Values that contain functional or abstract values can't be compared, and the
+    /// `Invalid_argument` exception raised by the comparison is returned as an error.
+    pub fn new(val: OCaml<T>) -> Result<Self, OCamlException> {
+        Self::try_from(OCamlGlobalRoot::new(val))
+    }
+
+    /// Returns an [`OCamlRef`] that points to the value kept by this key.
+    pub fn as_ref(&self) -> OCamlRef<'_, T> {
+        self.root.as_ref()
+    }
+
+    /// Returns the OCaml value kept by this key.
+    pub fn get<'a>(&self, cr: &'a OCamlRuntime) -> OCaml<'a, T> {
+        self.root.get(cr)
+    }
+
+    /// Returns the global root that keeps the value.
+    pub fn into_root(self) -> OCamlGlobalRoot<T> {
+        self.root
+    }
+}
+
+impl<T> TryFrom<OCamlGlobalRoot<T>> for OCamlKey<T> {
+    type Error = OCamlException;
+
+    fn try_from(root: OCamlGlobalRoot<T>) -> Result<Self, Self::Error> {
+        let cr = unsafe { OCamlRuntime::recover_handle() };
+        // Unlike `compare`, `=` inspects values that are physically equal,
+        // so this fails if the value can't be compared.
+        root.as_ref().equal(cr, root.as_ref())?;
+        Ok(OCamlKey { root })
+    }
+}
+
+impl<T> Ord for OCamlKey<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let cr = unsafe { OCamlRuntime::recover_handle() };
+        match self.as_ref().compare(cr, other.as_ref()) {
+            Ok(ordering) => ordering,
+            Err(exn) => panic!(
+                "OCaml values can't be compared: {}",
+                exn.message().unwrap_or_default()
+            ),
+        }
+    }
+}
+
+impl<T> PartialOrd for OCamlKey<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> PartialEq for OCamlKey<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<T> Eq for OCamlKey<T> {}
+
+impl<T> Hash for OCamlKey<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let cr = unsafe { OCamlRuntime::recover_handle() };
+        state.write_i64(self.as_ref().hash(cr));
+    }
+}
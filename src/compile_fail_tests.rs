@@ -72,3 +72,35 @@ pub struct OCamlImmediateRootEscapeFailureCheck;
 /// }
 /// ```
 pub struct NoStaticDerefsForNonImmediates;
+
+// Check that mutable references to the contents of OCaml bytes cannot be used after an allocation.
+// Must fail with:
+// error[E0499]: cannot borrow `*cr` as mutable more than once at a time
+/// ```compile_fail
+/// # use ocaml_interop::*;
+/// # let cr = &mut OCamlRuntime::init();
+/// ocaml_frame!(cr, (root), {
+/// let bytes: OCamlRef<OCamlBytes> = to_ocaml!(cr, "test", root);
+/// let contents = bytes.as_mut_bytes(cr);
+/// let other: OCaml<String> = "test".to_owned().to_ocaml(cr);
+/// contents[0] = b'T';
+/// # ()
+/// });
+/// ```
+pub struct MutableBytesLivenessFailureCheck;
+
+// Check that Rust values borrowed from OCaml values cannot be used after an allocation.
+// Must fail with:
+// error[E0502]: cannot borrow `*cr` as mutable because it is also borrowed as immutable
+/// ```compile_fail
+/// # use ocaml_interop::*;
+/// # let cr = &mut OCamlRuntime::init();
+/// ocaml_frame!(cr, (root), {
+/// let s: OCamlRef<String> = to_ocaml!(cr, "test", root);
+/// let borrowed: &str = s.to_rust_borrowed(cr);
+/// let other: OCaml<String> = "test".to_owned().to_ocaml(cr);
+/// println!("{}", borrowed);
+/// # ()
+/// });
+/// ```
+pub struct BorrowedConversionLivenessFailureCheck;
@@ -0,0 +1,59 @@
+// Copyright (c) SimpleStaking and Tezedge Contributors
+// SPDX-License-Identifier: MIT
+
+use core::task::{Context, Poll, Waker};
+use std::sync::Mutex;
+
+enum CompletionState<T> {
+    Pending(Option<Waker>),
+    Completed(T),
+    Taken,
+}
+
+// A value that is going to be produced in the future, by a different thread or
+// by a callback, and that can be polled by a `Future` implementation.
+pub(crate) struct Completion<T> {
+    state: Mutex<CompletionState<T>>,
+}
+
+impl<T> Completion<T> {
+    pub(crate) fn new() -> Self {
+        Completion {
+            state: Mutex::new(CompletionState::Pending(None)),
+        }
+    }
+
+    pub(crate) fn is_pending(&self) -> bool {
+        matches!(*self.state.lock().unwrap(), CompletionState::Pending(_))
+    }
+
+    // Stores the value and wakes up the task waiting for it. Only the first completion is kept.
+    pub(crate) fn complete(&self, value: T) {
+        let waker = {
+            let mut state = self.state.lock().unwrap();
+            match *state {
+                CompletionState::Pending(ref mut waker) => {
+                    let waker = waker.take();
+                    *state = CompletionState::Completed(value);
+                    waker
+                }
+                _ => None,
+            }
+        };
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+
+    pub(crate) fn poll(&self, cx: &mut Context) -> Poll<T> {
+        let mut state = self.state.lock().unwrap();
+        match core::mem::replace(&mut *state, CompletionState::Taken) {
+            CompletionState::Pending(_) => {
+                *state = CompletionState::Pending(Some(cx.waker().clone()));
+                Poll::Pending
+            }
+            CompletionState::Completed(value) => Poll::Ready(value),
+            CompletionState::Taken => panic!("future polled after completion"),
+        }
+    }
+}
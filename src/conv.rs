@@ -1,8 +1,18 @@
 // Copyright (c) SimpleStaking and Tezedge Contributors
 // SPDX-License-Identifier: MIT
 
+// Arrays are converted like tuples whose elements all have the same type, `OCamlA`.
+// Expands to `OCamlA` once for every tuple index it is applied to.
+macro_rules! array_tuple_element {
+    ($index:tt) => {
+        OCamlA
+    };
+}
+
 mod from_ocaml;
+mod from_ocaml_borrowed;
 mod to_ocaml;
 
 pub use self::from_ocaml::FromOCaml;
-pub use self::to_ocaml::ToOCaml;
+pub use self::from_ocaml_borrowed::FromOCamlBorrowed;
+pub use self::to_ocaml::{ToOCaml, TryToOCaml};
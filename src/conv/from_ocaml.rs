@@ -2,9 +2,18 @@
 // SPDX-License-Identifier: MIT
 
 use crate::{
-    mlvalues::{field_val, OCamlBytes, OCamlFloat, OCamlInt, OCamlInt32, OCamlInt64, OCamlList},
+    mlvalues::{
+        field_val, OCamlBytes, OCamlChar, OCamlFloat, OCamlInt, OCamlInt32, OCamlInt64, OCamlList,
+        OCamlNativeint, OCamlUchar,
+    },
     value::OCaml,
 };
+use core::{
+    convert::TryFrom,
+    num::{Saturating, Wrapping},
+};
+use ocaml_sys::int_val;
+use std::collections::VecDeque;
 
 /// Implements conversion from OCaml values into Rust values.
 pub unsafe trait FromOCaml<T> {
@@ -24,6 +33,72 @@ unsafe impl FromOCaml<OCamlInt> for i32 {
     }
 }
 
+// Integers other than `i64` and `i32` are read from OCaml fixnums with an explicit overflow
+// policy: plain integers are checked, and `Wrapping`/`Saturating` integers wrap around or
+// saturate at the bounds of the Rust type.
+macro_rules! impl_from_ocaml_int {
+    ($($rust_typ:ty),+ $(,)?) => {
+        $(
+            unsafe impl FromOCaml<OCamlInt> for $rust_typ {
+                /// # Panics
+                ///
+                /// Panics if the OCaml int doesn't fit in the Rust type.
+                fn from_ocaml(v: OCaml<OCamlInt>) -> Self {
+                    let n = v.to_i64();
+                    <$rust_typ>::try_from(n).unwrap_or_else(|_| {
+                        panic!("OCaml int n={} doesn't fit in {}", n, stringify!($rust_typ))
+                    })
+                }
+            }
+
+            unsafe impl FromOCaml<OCamlInt> for Wrapping<$rust_typ> {
+                fn from_ocaml(v: OCaml<OCamlInt>) -> Self {
+                    Wrapping(v.to_i64() as $rust_typ)
+                }
+            }
+
+            unsafe impl FromOCaml<OCamlInt> for Saturating<$rust_typ> {
+                fn from_ocaml(v: OCaml<OCamlInt>) -> Self {
+                    let n = v.to_i64();
+                    Saturating(<$rust_typ>::try_from(n).unwrap_or(if n < 0 {
+                        <$rust_typ>::MIN
+                    } else {
+                        <$rust_typ>::MAX
+                    }))
+                }
+            }
+        )+
+    };
+}
+
+impl_from_ocaml_int!(i8, i16, i128, isize, u8, u16, u32, u64, usize);
+
+unsafe impl FromOCaml<OCamlChar> for u8 {
+    fn from_ocaml(v: OCaml<OCamlChar>) -> Self {
+        unsafe { int_val(v.raw()) as u8 }
+    }
+}
+
+unsafe impl FromOCaml<OCamlChar> for char {
+    /// OCaml chars are interpreted as Latin-1 characters.
+    fn from_ocaml(v: OCaml<OCamlChar>) -> Self {
+        char::from(u8::from_ocaml(v))
+    }
+}
+
+unsafe impl FromOCaml<OCamlUchar> for char {
+    /// # Panics
+    ///
+    /// Panics if the value is not a valid Unicode scalar value.
+    fn from_ocaml(v: OCaml<OCamlUchar>) -> Self {
+        let n = unsafe { int_val(v.raw()) };
+        u32::try_from(n)
+            .ok()
+            .and_then(char::from_u32)
+            .unwrap_or_else(|| panic!("OCaml Uchar.t n={} is not a Unicode scalar value", n))
+    }
+}
+
 unsafe impl FromOCaml<OCamlInt32> for i32 {
     fn from_ocaml(v: OCaml<OCamlInt32>) -> Self {
         let val = unsafe { field_val(v.raw(), 1) };
@@ -38,6 +113,13 @@ unsafe impl FromOCaml<OCamlInt64> for i64 {
     }
 }
 
+unsafe impl FromOCaml<OCamlNativeint> for isize {
+    fn from_ocaml(v: OCaml<OCamlNativeint>) -> Self {
+        let val = unsafe { field_val(v.raw(), 1) };
+        unsafe { *(val as *const isize) }
+    }
+}
+
 unsafe impl FromOCaml<bool> for bool {
     fn from_ocaml(v: OCaml<bool>) -> Self {
         v.to_bool()
@@ -112,61 +194,78 @@ where
     }
 }
 
-unsafe impl<A, B, OCamlA, OCamlB> FromOCaml<(OCamlA, OCamlB)> for (A, B)
-where
-    A: FromOCaml<OCamlA>,
-    B: FromOCaml<OCamlB>,
-{
-    fn from_ocaml(v: OCaml<(OCamlA, OCamlB)>) -> Self {
-        (A::from_ocaml(v.fst()), B::from_ocaml(v.snd()))
-    }
-}
+macro_rules! impl_from_ocaml_tuple {
+    ($($typ:ident: $ocaml_typ:ident => $index:tt),+ $(,)?) => {
+        unsafe impl<$($typ, $ocaml_typ),+> FromOCaml<($($ocaml_typ),+)> for ($($typ),+)
+        where
+            $($typ: FromOCaml<$ocaml_typ>),+
+        {
+            fn from_ocaml(v: OCaml<($($ocaml_typ),+)>) -> Self {
+                ($($typ::from_ocaml(v.element::<$index>())),+)
+            }
+        }
 
-unsafe impl<A, B, C, OCamlA, OCamlB, OCamlC> FromOCaml<(OCamlA, OCamlB, OCamlC)> for (A, B, C)
-where
-    A: FromOCaml<OCamlA>,
-    B: FromOCaml<OCamlB>,
-    C: FromOCaml<OCamlC>,
-{
-    fn from_ocaml(v: OCaml<(OCamlA, OCamlB, OCamlC)>) -> Self {
-        (
-            A::from_ocaml(v.fst()),
-            B::from_ocaml(v.snd()),
-            C::from_ocaml(v.tuple_3()),
-        )
-    }
+        unsafe impl<A, OCamlA> FromOCaml<($(array_tuple_element!($index)),+)>
+            for [A; [$($index),+].len()]
+        where
+            A: FromOCaml<OCamlA>,
+        {
+            fn from_ocaml(v: OCaml<($(array_tuple_element!($index)),+)>) -> Self {
+                [$(A::from_ocaml(v.element::<$index>())),+]
+            }
+        }
+    };
 }
 
-unsafe impl<A, B, C, D, OCamlA, OCamlB, OCamlC, OCamlD> FromOCaml<(OCamlA, OCamlB, OCamlC, OCamlD)>
-    for (A, B, C, D)
+impl_from_ocaml_tuple!(A: OCamlA => 0, B: OCamlB => 1);
+impl_from_ocaml_tuple!(A: OCamlA => 0, B: OCamlB => 1, C: OCamlC => 2);
+impl_from_ocaml_tuple!(A: OCamlA => 0, B: OCamlB => 1, C: OCamlC => 2, D: OCamlD => 3);
+impl_from_ocaml_tuple!(
+    A: OCamlA => 0, B: OCamlB => 1, C: OCamlC => 2, D: OCamlD => 3, E: OCamlE => 4,
+);
+impl_from_ocaml_tuple!(
+    A: OCamlA => 0, B: OCamlB => 1, C: OCamlC => 2, D: OCamlD => 3, E: OCamlE => 4, F: OCamlF => 5,
+);
+impl_from_ocaml_tuple!(
+    A: OCamlA => 0, B: OCamlB => 1, C: OCamlC => 2, D: OCamlD => 3, E: OCamlE => 4, F: OCamlF => 5,
+    G: OCamlG => 6,
+);
+impl_from_ocaml_tuple!(
+    A: OCamlA => 0, B: OCamlB => 1, C: OCamlC => 2, D: OCamlD => 3, E: OCamlE => 4, F: OCamlF => 5,
+    G: OCamlG => 6, H: OCamlH => 7,
+);
+impl_from_ocaml_tuple!(
+    A: OCamlA => 0, B: OCamlB => 1, C: OCamlC => 2, D: OCamlD => 3, E: OCamlE => 4, F: OCamlF => 5,
+    G: OCamlG => 6, H: OCamlH => 7, I: OCamlI => 8,
+);
+impl_from_ocaml_tuple!(
+    A: OCamlA => 0, B: OCamlB => 1, C: OCamlC => 2, D: OCamlD => 3, E: OCamlE => 4, F: OCamlF => 5,
+    G: OCamlG => 6, H: OCamlH => 7, I: OCamlI => 8, J: OCamlJ => 9,
+);
+impl_from_ocaml_tuple!(
+    A: OCamlA => 0, B: OCamlB => 1, C: OCamlC => 2, D: OCamlD => 3, E: OCamlE => 4, F: OCamlF => 5,
+    G: OCamlG => 6, H: OCamlH => 7, I: OCamlI => 8, J: OCamlJ => 9, K: OCamlK => 10,
+);
+impl_from_ocaml_tuple!(
+    A: OCamlA => 0, B: OCamlB => 1, C: OCamlC => 2, D: OCamlD => 3, E: OCamlE => 4, F: OCamlF => 5,
+    G: OCamlG => 6, H: OCamlH => 7, I: OCamlI => 8, J: OCamlJ => 9, K: OCamlK => 10,
+    L: OCamlL => 11,
+);
+
+unsafe impl<A, OCamlA> FromOCaml<OCamlList<OCamlA>> for Vec<A>
 where
     A: FromOCaml<OCamlA>,
-    B: FromOCaml<OCamlB>,
-    C: FromOCaml<OCamlC>,
-    D: FromOCaml<OCamlD>,
 {
-    fn from_ocaml(v: OCaml<(OCamlA, OCamlB, OCamlC, OCamlD)>) -> Self {
-        (
-            A::from_ocaml(v.fst()),
-            B::from_ocaml(v.snd()),
-            C::from_ocaml(v.tuple_3()),
-            D::from_ocaml(v.tuple_4()),
-        )
+    fn from_ocaml(v: OCaml<OCamlList<OCamlA>>) -> Self {
+        v.iter().map(A::from_ocaml).collect()
     }
 }
 
-unsafe impl<A, OCamlA> FromOCaml<OCamlList<OCamlA>> for Vec<A>
+unsafe impl<A, OCamlA> FromOCaml<OCamlList<OCamlA>> for VecDeque<A>
 where
     A: FromOCaml<OCamlA>,
 {
     fn from_ocaml(v: OCaml<OCamlList<OCamlA>>) -> Self {
-        // TODO: pre-calculate actual required capacity?
-        let mut vec = Vec::new();
-        let mut current = v;
-        while let Some((hd, tl)) = current.uncons() {
-            current = tl;
-            vec.push(A::from_ocaml(hd));
-        }
-        vec
+        v.iter().map(A::from_ocaml).collect()
     }
 }
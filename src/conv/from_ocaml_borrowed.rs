@@ -0,0 +1,183 @@
+// Copyright (c) SimpleStaking and Tezedge Contributors
+// SPDX-License-Identifier: MIT
+
+use crate::{
+    conv::FromOCaml,
+    mlvalues::{
+        OCamlBytes, OCamlChar, OCamlFloat, OCamlInt, OCamlInt32, OCamlInt64, OCamlList,
+        OCamlNativeint, OCamlUchar,
+    },
+    value::OCaml,
+};
+use std::borrow::Cow;
+
+/// Implements conversion from OCaml values into Rust values that may borrow from them.
+///
+/// The lifetime `'a` is the lifetime of the [`OCaml`] value, which is bound to a borrow of the
+/// OCaml runtime handle. The OCaml runtime cannot be used to allocate (and move values) while the
+/// converted Rust value is alive, so strings and bytes can be accessed without copying them.
+///
+/// # Safety
+///
+/// Implementations must only borrow memory that belongs to the OCaml value being converted.
+pub unsafe trait FromOCamlBorrowed<'a, T>: Sized {
+    /// Convert from OCaml value.
+    fn from_ocaml_borrowed(v: OCaml<'a, T>) -> Self;
+}
+
+unsafe impl<'a> FromOCamlBorrowed<'a, String> for &'a str {
+    /// # Panics
+    ///
+    /// Panics if the string is not valid utf8.
+    fn from_ocaml_borrowed(v: OCaml<'a, String>) -> Self {
+        v.as_str()
+    }
+}
+
+unsafe impl<'a> FromOCamlBorrowed<'a, String> for Cow<'a, str> {
+    fn from_ocaml_borrowed(v: OCaml<'a, String>) -> Self {
+        String::from_utf8_lossy(v.as_bytes())
+    }
+}
+
+unsafe impl<'a> FromOCamlBorrowed<'a, String> for &'a [u8] {
+    fn from_ocaml_borrowed(v: OCaml<'a, String>) -> Self {
+        v.as_bytes()
+    }
+}
+
+unsafe impl<'a> FromOCamlBorrowed<'a, OCamlBytes> for &'a [u8] {
+    fn from_ocaml_borrowed(v: OCaml<'a, OCamlBytes>) -> Self {
+        v.as_bytes()
+    }
+}
+
+// Owned values, so that they can be mixed with borrowed ones in records and tuples.
+macro_rules! impl_from_ocaml_borrowed_owned {
+    ($($ocaml_typ:ty => $rust_typ:ty),+ $(,)?) => {
+        $(
+            unsafe impl<'a> FromOCamlBorrowed<'a, $ocaml_typ> for $rust_typ {
+                fn from_ocaml_borrowed(v: OCaml<'a, $ocaml_typ>) -> Self {
+                    <$rust_typ as FromOCaml<$ocaml_typ>>::from_ocaml(v)
+                }
+            }
+        )+
+    };
+}
+
+impl_from_ocaml_borrowed_owned! {
+    OCamlInt => i64,
+    OCamlInt => i32,
+    OCamlInt => i8,
+    OCamlInt => i16,
+    OCamlInt => i128,
+    OCamlInt => isize,
+    OCamlInt => u8,
+    OCamlInt => u16,
+    OCamlInt => u32,
+    OCamlInt => u64,
+    OCamlInt => usize,
+    OCamlInt32 => i32,
+    OCamlInt64 => i64,
+    OCamlNativeint => isize,
+    OCamlChar => u8,
+    OCamlChar => char,
+    OCamlUchar => char,
+    bool => bool,
+    OCamlFloat => f64,
+    String => String,
+    String => Vec<u8>,
+    OCamlBytes => String,
+    OCamlBytes => Vec<u8>,
+}
+
+unsafe impl<'a, A, OCamlA> FromOCamlBorrowed<'a, Option<OCamlA>> for Option<A>
+where
+    A: FromOCamlBorrowed<'a, OCamlA>,
+{
+    fn from_ocaml_borrowed(v: OCaml<'a, Option<OCamlA>>) -> Self {
+        v.to_option().map(A::from_ocaml_borrowed)
+    }
+}
+
+unsafe impl<'a, A, OCamlA, Err, OCamlErr> FromOCamlBorrowed<'a, Result<OCamlA, OCamlErr>>
+    for Result<A, Err>
+where
+    A: FromOCamlBorrowed<'a, OCamlA>,
+    Err: FromOCamlBorrowed<'a, OCamlErr>,
+{
+    fn from_ocaml_borrowed(v: OCaml<'a, Result<OCamlA, OCamlErr>>) -> Self {
+        match v.to_result() {
+            Ok(ocaml_ok) => Ok(A::from_ocaml_borrowed(ocaml_ok)),
+            Err(ocaml_err) => Err(Err::from_ocaml_borrowed(ocaml_err)),
+        }
+    }
+}
+
+macro_rules! impl_from_ocaml_borrowed_tuple {
+    ($($typ:ident: $ocaml_typ:ident => $index:tt),+ $(,)?) => {
+        unsafe impl<'a, $($typ, $ocaml_typ),+> FromOCamlBorrowed<'a, ($($ocaml_typ),+)>
+            for ($($typ),+)
+        where
+            $($typ: FromOCamlBorrowed<'a, $ocaml_typ>),+
+        {
+            fn from_ocaml_borrowed(v: OCaml<'a, ($($ocaml_typ),+)>) -> Self {
+                ($($typ::from_ocaml_borrowed(v.element::<$index>())),+)
+            }
+        }
+
+        unsafe impl<'a, A, OCamlA> FromOCamlBorrowed<'a, ($(array_tuple_element!($index)),+)>
+            for [A; [$($index),+].len()]
+        where
+            A: FromOCamlBorrowed<'a, OCamlA>,
+        {
+            fn from_ocaml_borrowed(v: OCaml<'a, ($(array_tuple_element!($index)),+)>) -> Self {
+                [$(A::from_ocaml_borrowed(v.element::<$index>())),+]
+            }
+        }
+    };
+}
+
+impl_from_ocaml_borrowed_tuple!(A: OCamlA => 0, B: OCamlB => 1);
+impl_from_ocaml_borrowed_tuple!(A: OCamlA => 0, B: OCamlB => 1, C: OCamlC => 2);
+impl_from_ocaml_borrowed_tuple!(A: OCamlA => 0, B: OCamlB => 1, C: OCamlC => 2, D: OCamlD => 3);
+impl_from_ocaml_borrowed_tuple!(
+    A: OCamlA => 0, B: OCamlB => 1, C: OCamlC => 2, D: OCamlD => 3, E: OCamlE => 4,
+);
+impl_from_ocaml_borrowed_tuple!(
+    A: OCamlA => 0, B: OCamlB => 1, C: OCamlC => 2, D: OCamlD => 3, E: OCamlE => 4, F: OCamlF => 5,
+);
+impl_from_ocaml_borrowed_tuple!(
+    A: OCamlA => 0, B: OCamlB => 1, C: OCamlC => 2, D: OCamlD => 3, E: OCamlE => 4, F: OCamlF => 5,
+    G: OCamlG => 6,
+);
+impl_from_ocaml_borrowed_tuple!(
+    A: OCamlA => 0, B: OCamlB => 1, C: OCamlC => 2, D: OCamlD => 3, E: OCamlE => 4, F: OCamlF => 5,
+    G: OCamlG => 6, H: OCamlH => 7,
+);
+impl_from_ocaml_borrowed_tuple!(
+    A: OCamlA => 0, B: OCamlB => 1, C: OCamlC => 2, D: OCamlD => 3, E: OCamlE => 4, F: OCamlF => 5,
+    G: OCamlG => 6, H: OCamlH => 7, I: OCamlI => 8,
+);
+impl_from_ocaml_borrowed_tuple!(
+    A: OCamlA => 0, B: OCamlB => 1, C: OCamlC => 2, D: OCamlD => 3, E: OCamlE => 4, F: OCamlF => 5,
+    G: OCamlG => 6, H: OCamlH => 7, I: OCamlI => 8, J: OCamlJ => 9,
+);
+impl_from_ocaml_borrowed_tuple!(
+    A: OCamlA => 0, B: OCamlB => 1, C: OCamlC => 2, D: OCamlD => 3, E: OCamlE => 4, F: OCamlF => 5,
+    G: OCamlG => 6, H: OCamlH => 7, I: OCamlI => 8, J: OCamlJ => 9, K: OCamlK => 10,
+);
+impl_from_ocaml_borrowed_tuple!(
+    A: OCamlA => 0, B: OCamlB => 1, C: OCamlC => 2, D: OCamlD => 3, E: OCamlE => 4, F: OCamlF => 5,
+    G: OCamlG => 6, H: OCamlH => 7, I: OCamlI => 8, J: OCamlJ => 9, K: OCamlK => 10,
+    L: OCamlL => 11,
+);
+
+unsafe impl<'a, A, OCamlA> FromOCamlBorrowed<'a, OCamlList<OCamlA>> for Vec<A>
+where
+    A: FromOCamlBorrowed<'a, OCamlA>,
+{
+    fn from_ocaml_borrowed(v: OCaml<'a, OCamlList<OCamlA>>) -> Self {
+        v.iter().map(A::from_ocaml_borrowed).collect()
+    }
+}
@@ -1,17 +1,26 @@
 // Copyright (c) SimpleStaking and Tezedge Contributors
 // SPDX-License-Identifier: MIT
 
-use core::str;
-use ocaml_sys::{caml_alloc, store_field};
+use core::{
+    char::TryFromCharError,
+    convert::TryFrom,
+    num::{Saturating, Wrapping},
+    str,
+};
+use ocaml_sys::{caml_alloc, store_field, val_int};
+use std::collections::VecDeque;
 
 use crate::{
+    error::OCamlFixnumConversionError,
     memory::{
-        alloc_bytes, alloc_cons, alloc_double, alloc_int32, alloc_int64, alloc_some, alloc_string,
-        alloc_tuple, alloc_tuple_3, alloc_tuple_4, OCamlRef,
+        alloc_bytes, alloc_double, alloc_int32, alloc_int64, alloc_nativeint, alloc_some,
+        alloc_string, alloc_tuple, alloc_tuple_10, alloc_tuple_11, alloc_tuple_12, alloc_tuple_3,
+        alloc_tuple_4, alloc_tuple_5, alloc_tuple_6, alloc_tuple_7, alloc_tuple_8, alloc_tuple_9,
+        OCamlCell,
     },
     mlvalues::{
-        tag, OCamlBytes, OCamlFloat, OCamlInt, OCamlInt32, OCamlInt64, OCamlList, RawOCaml, FALSE,
-        NONE, TRUE,
+        tag, OCamlBytes, OCamlChar, OCamlFloat, OCamlInt, OCamlInt32, OCamlInt64, OCamlList,
+        OCamlNativeint, OCamlUchar, FALSE, NONE, TRUE,
     },
     ocaml_frame,
     runtime::OCamlRuntime,
@@ -25,21 +34,111 @@ pub unsafe trait ToOCaml<T> {
     fn to_ocaml<'a>(&self, cr: &'a mut OCamlRuntime) -> OCaml<'a, T>;
 }
 
-unsafe impl<'root, T> ToOCaml<T> for OCamlRef<'root, T> {
+/// Implements fallible conversion from Rust values into OCaml values, for the values that
+/// don't always fit in the OCaml type and whose [`ToOCaml`] conversion panics instead.
+///
+/// # Safety
+///
+/// Like for [`ToOCaml`], the returned value must be a valid OCaml value of type `T`.
+pub unsafe trait TryToOCaml<T> {
+    /// The error returned when the value doesn't fit in the OCaml type.
+    type Error;
+
+    /// Convert to OCaml value, or return an error if the value doesn't fit.
+    fn try_to_ocaml<'a>(&self, cr: &'a mut OCamlRuntime) -> Result<OCaml<'a, T>, Self::Error>;
+}
+
+unsafe impl<T> ToOCaml<T> for OCamlCell<T> {
     fn to_ocaml<'a>(&self, cr: &'a mut OCamlRuntime) -> OCaml<'a, T> {
         unsafe { OCaml::new(cr, self.get_raw()) }
     }
 }
 
-unsafe impl ToOCaml<OCamlInt> for i64 {
-    fn to_ocaml<'a>(&self, cr: &'a mut OCamlRuntime) -> OCaml<'a, OCamlInt> {
-        unsafe { OCaml::new(cr, ((self << 1) | 1) as RawOCaml) }
+// Integers are converted into OCaml fixnums with an explicit overflow policy:
+// plain integers are checked, and `Wrapping`/`Saturating` integers wrap around or
+// saturate at the fixnum bounds.
+macro_rules! impl_to_ocaml_int {
+    ($($rust_typ:ty),+ $(,)?) => {
+        $(
+            unsafe impl ToOCaml<OCamlInt> for $rust_typ {
+                /// # Panics
+                ///
+                /// Panics with an `OCamlFixnumConversionError` message if the value doesn't
+                /// fit in an OCaml fixnum. Use [`TryToOCaml::try_to_ocaml`] to get the error
+                /// instead.
+                fn to_ocaml<'a>(&self, cr: &'a mut OCamlRuntime) -> OCaml<'a, OCamlInt> {
+                    match self.try_to_ocaml(cr) {
+                        Ok(n) => n,
+                        Err(err) => panic!("{}", err),
+                    }
+                }
+            }
+
+            unsafe impl TryToOCaml<OCamlInt> for $rust_typ {
+                type Error = OCamlFixnumConversionError;
+
+                fn try_to_ocaml<'a>(
+                    &self,
+                    cr: &'a mut OCamlRuntime,
+                ) -> Result<OCaml<'a, OCamlInt>, OCamlFixnumConversionError> {
+                    let n = OCaml::of_i128(*self as i128)?;
+                    Ok(unsafe { OCaml::new(cr, n.raw()) })
+                }
+            }
+
+            unsafe impl ToOCaml<OCamlInt> for Wrapping<$rust_typ> {
+                fn to_ocaml<'a>(&self, cr: &'a mut OCamlRuntime) -> OCaml<'a, OCamlInt> {
+                    let n = OCaml::of_i128_wrapping(self.0 as i128);
+                    unsafe { OCaml::new(cr, n.raw()) }
+                }
+            }
+
+            unsafe impl ToOCaml<OCamlInt> for Saturating<$rust_typ> {
+                fn to_ocaml<'a>(&self, cr: &'a mut OCamlRuntime) -> OCaml<'a, OCamlInt> {
+                    let n = OCaml::of_i128_saturating(self.0 as i128);
+                    unsafe { OCaml::new(cr, n.raw()) }
+                }
+            }
+        )+
+    };
+}
+
+impl_to_ocaml_int!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, usize);
+
+unsafe impl ToOCaml<OCamlChar> for u8 {
+    fn to_ocaml<'a>(&self, cr: &'a mut OCamlRuntime) -> OCaml<'a, OCamlChar> {
+        unsafe { OCaml::new(cr, val_int(*self as isize)) }
+    }
+}
+
+unsafe impl ToOCaml<OCamlChar> for char {
+    /// # Panics
+    ///
+    /// Panics if the character is not in the `'\0'..='\u{ff}'` (Latin-1) range. Use
+    /// [`TryToOCaml::try_to_ocaml`] to get the error instead.
+    fn to_ocaml<'a>(&self, cr: &'a mut OCamlRuntime) -> OCaml<'a, OCamlChar> {
+        match self.try_to_ocaml(cr) {
+            Ok(c) => c,
+            Err(_) => panic!("Character {:?} doesn't fit in an OCaml char", self),
+        }
     }
 }
 
-unsafe impl ToOCaml<OCamlInt> for i32 {
-    fn to_ocaml<'a>(&self, cr: &'a mut OCamlRuntime) -> OCaml<'a, OCamlInt> {
-        (*self as i64).to_ocaml(cr)
+unsafe impl TryToOCaml<OCamlChar> for char {
+    type Error = TryFromCharError;
+
+    fn try_to_ocaml<'a>(
+        &self,
+        cr: &'a mut OCamlRuntime,
+    ) -> Result<OCaml<'a, OCamlChar>, TryFromCharError> {
+        let c = u8::try_from(*self)?;
+        Ok(c.to_ocaml(cr))
+    }
+}
+
+unsafe impl ToOCaml<OCamlUchar> for char {
+    fn to_ocaml<'a>(&self, cr: &'a mut OCamlRuntime) -> OCaml<'a, OCamlUchar> {
+        unsafe { OCaml::new(cr, val_int(*self as isize)) }
     }
 }
 
@@ -55,6 +154,12 @@ unsafe impl ToOCaml<OCamlInt64> for i64 {
     }
 }
 
+unsafe impl ToOCaml<OCamlNativeint> for isize {
+    fn to_ocaml<'a>(&self, cr: &'a mut OCamlRuntime) -> OCaml<'a, OCamlNativeint> {
+        alloc_nativeint(cr, *self)
+    }
+}
+
 unsafe impl ToOCaml<OCamlFloat> for f64 {
     fn to_ocaml<'a>(&self, cr: &'a mut OCamlRuntime) -> OCaml<'a, OCamlFloat> {
         alloc_double(cr, *self)
@@ -163,80 +268,114 @@ where
     }
 }
 
-unsafe impl<A, B, OCamlA, OCamlB> ToOCaml<(OCamlA, OCamlB)> for (A, B)
-where
-    A: ToOCaml<OCamlA>,
-    B: ToOCaml<OCamlB>,
-{
-    fn to_ocaml<'a>(&self, cr: &'a mut OCamlRuntime) -> OCaml<'a, (OCamlA, OCamlB)> {
-        ocaml_frame!(cr, (fst, snd), {
-            let fst = to_ocaml!(cr, self.0, fst);
-            let snd = to_ocaml!(cr, self.1, snd);
-            alloc_tuple(cr, fst, snd)
-        })
-    }
-}
+macro_rules! impl_to_ocaml_tuple {
+    ($alloc:ident, $($typ:ident: $ocaml_typ:ident => $root:ident . $index:tt),+ $(,)?) => {
+        unsafe impl<$($typ, $ocaml_typ),+> ToOCaml<($($ocaml_typ),+)> for ($($typ),+)
+        where
+            $($typ: ToOCaml<$ocaml_typ>),+
+        {
+            fn to_ocaml<'a>(&self, cr: &'a mut OCamlRuntime) -> OCaml<'a, ($($ocaml_typ),+)> {
+                ocaml_frame!(cr, ($($root),+), {
+                    $(let $root = to_ocaml!(cr, self.$index, $root);)+
+                    $alloc(cr, $($root),+)
+                })
+            }
+        }
 
-unsafe impl<A, B, C, OCamlA, OCamlB, OCamlC> ToOCaml<(OCamlA, OCamlB, OCamlC)> for (A, B, C)
-where
-    A: ToOCaml<OCamlA>,
-    B: ToOCaml<OCamlB>,
-    C: ToOCaml<OCamlC>,
-{
-    fn to_ocaml<'a>(&self, cr: &'a mut OCamlRuntime) -> OCaml<'a, (OCamlA, OCamlB, OCamlC)> {
-        ocaml_frame!(cr, (fst, snd, elt3), {
-            let fst = to_ocaml!(cr, self.0, fst);
-            let snd = to_ocaml!(cr, self.1, snd);
-            let elt3 = to_ocaml!(cr, self.2, elt3);
-            alloc_tuple_3(cr, fst, snd, elt3)
-        })
-    }
+        unsafe impl<A, OCamlA> ToOCaml<($(array_tuple_element!($index)),+)>
+            for [A; [$($index),+].len()]
+        where
+            A: ToOCaml<OCamlA>,
+        {
+            fn to_ocaml<'a>(
+                &self,
+                cr: &'a mut OCamlRuntime,
+            ) -> OCaml<'a, ($(array_tuple_element!($index)),+)> {
+                ocaml_frame!(cr, ($($root),+), {
+                    $(let $root = to_ocaml!(cr, self[$index], $root);)+
+                    $alloc(cr, $($root),+)
+                })
+            }
+        }
+    };
 }
 
-unsafe impl<A, B, C, D, OCamlA, OCamlB, OCamlC, OCamlD> ToOCaml<(OCamlA, OCamlB, OCamlC, OCamlD)>
-    for (A, B, C, D)
+impl_to_ocaml_tuple!(alloc_tuple, A: OCamlA => fst.0, B: OCamlB => snd.1);
+impl_to_ocaml_tuple!(alloc_tuple_3, A: OCamlA => fst.0, B: OCamlB => snd.1, C: OCamlC => elt3.2);
+impl_to_ocaml_tuple!(
+    alloc_tuple_4,
+    A: OCamlA => fst.0, B: OCamlB => snd.1, C: OCamlC => elt3.2, D: OCamlD => elt4.3,
+);
+impl_to_ocaml_tuple!(
+    alloc_tuple_5,
+    A: OCamlA => fst.0, B: OCamlB => snd.1, C: OCamlC => elt3.2, D: OCamlD => elt4.3,
+    E: OCamlE => elt5.4,
+);
+impl_to_ocaml_tuple!(
+    alloc_tuple_6,
+    A: OCamlA => fst.0, B: OCamlB => snd.1, C: OCamlC => elt3.2, D: OCamlD => elt4.3,
+    E: OCamlE => elt5.4, F: OCamlF => elt6.5,
+);
+impl_to_ocaml_tuple!(
+    alloc_tuple_7,
+    A: OCamlA => fst.0, B: OCamlB => snd.1, C: OCamlC => elt3.2, D: OCamlD => elt4.3,
+    E: OCamlE => elt5.4, F: OCamlF => elt6.5, G: OCamlG => elt7.6,
+);
+impl_to_ocaml_tuple!(
+    alloc_tuple_8,
+    A: OCamlA => fst.0, B: OCamlB => snd.1, C: OCamlC => elt3.2, D: OCamlD => elt4.3,
+    E: OCamlE => elt5.4, F: OCamlF => elt6.5, G: OCamlG => elt7.6, H: OCamlH => elt8.7,
+);
+impl_to_ocaml_tuple!(
+    alloc_tuple_9,
+    A: OCamlA => fst.0, B: OCamlB => snd.1, C: OCamlC => elt3.2, D: OCamlD => elt4.3,
+    E: OCamlE => elt5.4, F: OCamlF => elt6.5, G: OCamlG => elt7.6, H: OCamlH => elt8.7,
+    I: OCamlI => elt9.8,
+);
+impl_to_ocaml_tuple!(
+    alloc_tuple_10,
+    A: OCamlA => fst.0, B: OCamlB => snd.1, C: OCamlC => elt3.2, D: OCamlD => elt4.3,
+    E: OCamlE => elt5.4, F: OCamlF => elt6.5, G: OCamlG => elt7.6, H: OCamlH => elt8.7,
+    I: OCamlI => elt9.8, J: OCamlJ => elt10.9,
+);
+impl_to_ocaml_tuple!(
+    alloc_tuple_11,
+    A: OCamlA => fst.0, B: OCamlB => snd.1, C: OCamlC => elt3.2, D: OCamlD => elt4.3,
+    E: OCamlE => elt5.4, F: OCamlF => elt6.5, G: OCamlG => elt7.6, H: OCamlH => elt8.7,
+    I: OCamlI => elt9.8, J: OCamlJ => elt10.9, K: OCamlK => elt11.10,
+);
+impl_to_ocaml_tuple!(
+    alloc_tuple_12,
+    A: OCamlA => fst.0, B: OCamlB => snd.1, C: OCamlC => elt3.2, D: OCamlD => elt4.3,
+    E: OCamlE => elt5.4, F: OCamlF => elt6.5, G: OCamlG => elt7.6, H: OCamlH => elt8.7,
+    I: OCamlI => elt9.8, J: OCamlJ => elt10.9, K: OCamlK => elt11.10, L: OCamlL => elt12.11,
+);
+
+unsafe impl<A, OCamlA> ToOCaml<OCamlList<OCamlA>> for Vec<A>
 where
     A: ToOCaml<OCamlA>,
-    B: ToOCaml<OCamlB>,
-    C: ToOCaml<OCamlC>,
-    D: ToOCaml<OCamlD>,
 {
-    fn to_ocaml<'a>(
-        &self,
-        cr: &'a mut OCamlRuntime,
-    ) -> OCaml<'a, (OCamlA, OCamlB, OCamlC, OCamlD)> {
-        ocaml_frame!(cr, (fst, snd, elt3, elt4), {
-            let fst = to_ocaml!(cr, self.0, fst);
-            let snd = to_ocaml!(cr, self.1, snd);
-            let elt3 = to_ocaml!(cr, self.2, elt3);
-            let elt4 = to_ocaml!(cr, self.3, elt4);
-            alloc_tuple_4(cr, fst, snd, elt3, elt4)
-        })
+    fn to_ocaml<'a>(&self, cr: &'a mut OCamlRuntime) -> OCaml<'a, OCamlList<OCamlA>> {
+        OCaml::of_iter(cr, self.iter())
     }
 }
 
-unsafe impl<A, OCamlA> ToOCaml<OCamlList<OCamlA>> for Vec<A>
+unsafe impl<A, OCamlA> ToOCaml<OCamlList<OCamlA>> for VecDeque<A>
 where
     A: ToOCaml<OCamlA>,
 {
     fn to_ocaml<'a>(&self, cr: &'a mut OCamlRuntime) -> OCaml<'a, OCamlList<OCamlA>> {
-        (&self).to_ocaml(cr)
+        OCaml::of_iter(cr, self.iter())
     }
 }
 
-unsafe impl<A, OCamlA> ToOCaml<OCamlList<OCamlA>> for &Vec<A>
+// References (including `OCamlRef<T>`) convert like the value they point to. This replaces
+// the implementation for `OCamlRef<T>`, and reference types can't implement `ToOCaml` otherwise.
+unsafe impl<A, OCamlA> ToOCaml<OCamlA> for &A
 where
-    A: ToOCaml<OCamlA>,
+    A: ToOCaml<OCamlA> + ?Sized,
 {
-    fn to_ocaml<'a>(&self, cr: &'a mut OCamlRuntime) -> OCaml<'a, OCamlList<OCamlA>> {
-        ocaml_frame!(cr, (result_root, ov_root), {
-            let mut result = result_root.keep(OCaml::nil());
-            for elt in self.iter().rev() {
-                let ov = to_ocaml!(cr, elt, ov_root);
-                let cons = alloc_cons(cr, ov, result);
-                result = result_root.keep(cons);
-            }
-            cr.get(result)
-        })
+    fn to_ocaml<'a>(&self, cr: &'a mut OCamlRuntime) -> OCaml<'a, OCamlA> {
+        (**self).to_ocaml(cr)
     }
 }
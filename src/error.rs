@@ -14,8 +14,8 @@ pub struct OCamlException {
 
 #[derive(Debug)]
 pub enum OCamlFixnumConversionError {
-    InputTooBig(i64),
-    InputTooSmall(i64),
+    InputTooBig(i128),
+    InputTooSmall(i128),
 }
 
 impl fmt::Display for OCamlFixnumConversionError {
@@ -35,6 +35,87 @@ impl fmt::Display for OCamlFixnumConversionError {
     }
 }
 
+/// Error produced when an OCaml Lwt promise is rejected.
+#[derive(Debug)]
+pub struct OCamlLwtRejection {
+    message: Option<String>,
+}
+
+impl OCamlLwtRejection {
+    pub(crate) fn new(message: Option<String>) -> Self {
+        OCamlLwtRejection { message }
+    }
+
+    /// Message of the exception that rejected the promise, if available.
+    pub fn message(&self) -> Option<&str> {
+        self.message.as_deref()
+    }
+}
+
+impl fmt::Display for OCamlLwtRejection {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.message {
+            Some(message) => write!(f, "Lwt promise rejected: {}", message),
+            None => write!(f, "Lwt promise rejected"),
+        }
+    }
+}
+
+/// Error produced when a function declared with [`ocaml!`](crate::ocaml) has not been
+/// registered from OCaml with `Callback.register`.
+#[derive(Debug)]
+pub struct OCamlFunctionNotRegistered {
+    name: &'static str,
+}
+
+impl OCamlFunctionNotRegistered {
+    pub(crate) fn new(name: &'static str) -> Self {
+        OCamlFunctionNotRegistered { name }
+    }
+
+    /// Name under which the function was expected to be registered.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+}
+
+impl fmt::Display for OCamlFunctionNotRegistered {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "OCaml closure with name '{}' not registered", self.name)
+    }
+}
+
+/// Error produced when the OCaml runtime fails to initialize.
+#[derive(Debug)]
+pub enum OCamlRuntimeInitError {
+    /// The program name, an argument or a runtime parameter contains a nul byte.
+    InvalidArgument(String),
+    /// The initialization of the OCaml runtime raised an exception.
+    Exception(Option<String>),
+}
+
+impl fmt::Display for OCamlRuntimeInitError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OCamlRuntimeInitError::InvalidArgument(arg) => {
+                write!(
+                    f,
+                    "Invalid OCaml runtime argument (contains a nul byte): {:?}",
+                    arg
+                )
+            }
+            OCamlRuntimeInitError::Exception(Some(message)) => write!(
+                f,
+                "OCaml runtime initialization raised an exception: {}",
+                message
+            ),
+            OCamlRuntimeInitError::Exception(None) => {
+                write!(f, "OCaml runtime initialization raised an exception")
+            }
+        }
+    }
+}
+
 impl OCamlException {
     #[doc(hidden)]
     pub unsafe fn of(raw: RawOCaml) -> Self {
@@ -0,0 +1,382 @@
+// Copyright (c) SimpleStaking and Tezedge Contributors
+// SPDX-License-Identifier: MIT
+
+use crate::{
+    memory::{caml_alloc, store_field, OCamlCell},
+    mlvalues::{field_val, wosize_val, OCamlFunction, RawOCaml, UNIT},
+    ocaml_closure_reference, ocaml_frame,
+    runtime::{OCamlAllocationPolicy, OCamlRuntime},
+    OCaml, OCamlRef,
+};
+use core::cell::{Cell, RefCell};
+use ocaml_sys::{int_val, val_int};
+use std::rc::Rc;
+
+// Primitives used by the `Gc` module of OCaml's standard library.
+extern "C" {
+    fn caml_gc_minor(unit: RawOCaml) -> RawOCaml;
+    fn caml_gc_major(unit: RawOCaml) -> RawOCaml;
+    fn caml_gc_full_major(unit: RawOCaml) -> RawOCaml;
+    fn caml_gc_compaction(unit: RawOCaml) -> RawOCaml;
+    fn caml_gc_stat(unit: RawOCaml) -> RawOCaml;
+    fn caml_gc_quick_stat(unit: RawOCaml) -> RawOCaml;
+    fn caml_gc_counters(unit: RawOCaml) -> RawOCaml;
+    fn caml_gc_get(unit: RawOCaml) -> RawOCaml;
+    fn caml_gc_set(control: RawOCaml) -> RawOCaml;
+    fn caml_adjust_gc_speed(res: usize, max: usize);
+    fn caml_alloc_dependent_memory(bytes: usize);
+    fn caml_free_dependent_memory(bytes: usize);
+}
+
+/// Statistics of the OCaml GC, as returned by `Gc.stat` and `Gc.quick_stat`.
+///
+/// See the documentation of OCaml's `Gc.stat` type for the meaning of each field.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OCamlGcStat {
+    pub minor_words: f64,
+    pub promoted_words: f64,
+    pub major_words: f64,
+    pub minor_collections: usize,
+    pub major_collections: usize,
+    pub heap_words: usize,
+    pub heap_chunks: usize,
+    pub live_words: usize,
+    pub live_blocks: usize,
+    pub free_words: usize,
+    pub free_blocks: usize,
+    pub largest_free: usize,
+    pub fragments: usize,
+    pub compactions: usize,
+    pub top_heap_words: usize,
+    pub stack_size: usize,
+    /// Only available since OCaml 4.12.
+    pub forced_major_collections: Option<usize>,
+}
+
+/// Allocation counters of the OCaml GC, as returned by `Gc.counters`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OCamlGcCounters {
+    pub minor_words: f64,
+    pub promoted_words: f64,
+    pub major_words: f64,
+}
+
+/// Parameters of the OCaml GC, as returned by `Gc.get` and accepted by `Gc.set`.
+///
+/// See the documentation of OCaml's `Gc.control` type for the meaning of each field.
+///
+/// Fields that the running version of OCaml doesn't have (`window_size` before 4.03, the
+/// `custom_*` fields before 4.08) are read as their default value and ignored when set.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OCamlGcControl {
+    pub minor_heap_size: usize,
+    pub major_heap_increment: usize,
+    pub space_overhead: usize,
+    pub verbose: usize,
+    pub max_overhead: usize,
+    pub stack_limit: usize,
+    pub allocation_policy: OCamlAllocationPolicy,
+    pub window_size: usize,
+    pub custom_major_ratio: usize,
+    pub custom_minor_ratio: usize,
+    pub custom_minor_max_size: usize,
+}
+
+/// Access to the OCaml garbage collector, obtained with [`OCamlRuntime::gc`].
+pub struct OCamlGc<'a> {
+    cr: &'a mut OCamlRuntime,
+}
+
+impl OCamlRuntime {
+    /// Returns a handle to control the OCaml GC and read its statistics.
+    pub fn gc(&mut self) -> OCamlGc<'_> {
+        OCamlGc { cr: self }
+    }
+}
+
+impl<'a> OCamlGc<'a> {
+    /// Performs a minor collection (`Gc.minor`).
+    pub fn minor(&mut self) {
+        unsafe { caml_gc_minor(UNIT) };
+    }
+
+    /// Performs a minor collection and finishes the current major cycle (`Gc.major`).
+    pub fn major(&mut self) {
+        unsafe { caml_gc_major(UNIT) };
+    }
+
+    /// Performs a minor collection and finishes the current major cycle, then
+    /// performs a complete new cycle (`Gc.full_major`).
+    pub fn full_major(&mut self) {
+        unsafe { caml_gc_full_major(UNIT) };
+    }
+
+    /// Performs a full major collection and compacts the heap (`Gc.compact`).
+    pub fn compact(&mut self) {
+        unsafe { caml_gc_compaction(UNIT) };
+    }
+
+    /// Returns the GC statistics, scanning the whole heap to compute them (`Gc.stat`).
+    pub fn stat(&mut self) -> OCamlGcStat {
+        unsafe { read_stat(caml_gc_stat(UNIT)) }
+    }
+
+    /// Returns the GC statistics, without the fields that require scanning the heap, which
+    /// are set to `0` (`Gc.quick_stat`).
+    pub fn quick_stat(&mut self) -> OCamlGcStat {
+        unsafe { read_stat(caml_gc_quick_stat(UNIT)) }
+    }
+
+    /// Returns the allocation counters of the GC (`Gc.counters`).
+    pub fn counters(&mut self) -> OCamlGcCounters {
+        unsafe {
+            let counters = caml_gc_counters(UNIT);
+            OCamlGcCounters {
+                minor_words: float_field(counters, 0),
+                promoted_words: float_field(counters, 1),
+                major_words: float_field(counters, 2),
+            }
+        }
+    }
+
+    /// Returns the current parameters of the GC (`Gc.get`).
+    pub fn control(&mut self) -> OCamlGcControl {
+        unsafe {
+            let control = caml_gc_get(UNIT);
+            // Older versions of OCaml have fewer fields, the missing ones
+            // are left at the default values of the versions that have them
+            let field = |i, default| {
+                if wosize_val(control) > i {
+                    int_field(control, i)
+                } else {
+                    default
+                }
+            };
+            OCamlGcControl {
+                minor_heap_size: int_field(control, 0),
+                major_heap_increment: int_field(control, 1),
+                space_overhead: int_field(control, 2),
+                verbose: int_field(control, 3),
+                max_overhead: int_field(control, 4),
+                stack_limit: int_field(control, 5),
+                allocation_policy: OCamlAllocationPolicy::from_param(int_field(control, 6)),
+                window_size: field(7, 1),
+                custom_major_ratio: field(8, 44),
+                custom_minor_ratio: field(9, 100),
+                custom_minor_max_size: field(10, 8192),
+            }
+        }
+    }
+
+    /// Changes the parameters of the GC (`Gc.set`).
+    pub fn set_control(&mut self, params: &OCamlGcControl) {
+        let fields = [
+            params.minor_heap_size,
+            params.major_heap_increment,
+            params.space_overhead,
+            params.verbose,
+            params.max_overhead,
+            params.stack_limit,
+            params.allocation_policy.to_param(),
+            params.window_size,
+            params.custom_major_ratio,
+            params.custom_minor_ratio,
+            params.custom_minor_max_size,
+        ];
+        unsafe {
+            // Starts from the current parameters so that fields unknown to this
+            // library (added by newer versions of OCaml) are preserved, and skips
+            // the fields that older versions of OCaml don't have.
+            let control = caml_gc_get(UNIT);
+            let len = wosize_val(control).min(fields.len());
+            for (i, value) in fields.iter().take(len).enumerate() {
+                store_field(control, i, val_int(*value as isize));
+            }
+            caml_gc_set(control);
+        }
+    }
+
+    /// Speeds up the major GC to account for `res` bytes of resources owned by OCaml
+    /// values outside of the OCaml heap, out of a total of `max` (`caml_adjust_gc_speed`).
+    ///
+    /// The GC will do the equivalent of a full major cycle when the accounted resources
+    /// add up to `max`.
+    pub fn adjust_speed(&mut self, res: usize, max: usize) {
+        unsafe { caml_adjust_gc_speed(res, max) };
+    }
+
+    /// Registers `f` to be called at the end of each major GC cycle (`Gc.create_alarm`).
+    ///
+    /// The alarm stays active until the returned [`OCamlGcAlarm`] is deleted or dropped.
+    ///
+    /// # Note
+    ///
+    /// Requires the `ocaml_interop` OCaml library (in the `ocaml/` directory of this repository)
+    /// to be linked into the program.
+    pub fn create_alarm<F>(&mut self, f: F) -> OCamlGcAlarm
+    where
+        F: FnMut(&mut OCamlRuntime) + 'static,
+    {
+        let state = Rc::new(AlarmState {
+            active: Cell::new(true),
+            callback: RefCell::new(Box::new(f)),
+        });
+        arm_alarm(self.cr, state.clone());
+        OCamlGcAlarm { state }
+    }
+}
+
+/// An alarm created with [`OCamlGc::create_alarm`].
+pub struct OCamlGcAlarm {
+    state: Rc<AlarmState>,
+}
+
+impl OCamlGcAlarm {
+    /// Stops the alarm (`Gc.delete_alarm`).
+    pub fn delete(self) {}
+}
+
+impl Drop for OCamlGcAlarm {
+    fn drop(&mut self) {
+        self.state.active.set(false);
+    }
+}
+
+type AlarmCallback = Box<dyn FnMut(&mut OCamlRuntime)>;
+
+struct AlarmState {
+    active: Cell<bool>,
+    callback: RefCell<AlarmCallback>,
+}
+
+// `OCaml<AlarmToken>` is a reference to a fresh block whose finalisation signals the end of a major cycle.
+struct AlarmToken {}
+
+// Same approach as `Gc.create_alarm`: a finaliser is attached to a fresh value, and when
+// it runs at the end of the major cycle, the alarm is rearmed with a new value.
+fn arm_alarm(cr: &mut OCamlRuntime, state: Rc<AlarmState>) {
+    ocaml_frame!(cr, (token_root), {
+        let token: OCaml<AlarmToken> = unsafe { OCaml::new(cr, caml_alloc(1, 0)) };
+        let token = token_root.keep(token);
+        token.finalise_last(cr, move |cr| {
+            if state.active.get() {
+                (state.callback.borrow_mut())(cr);
+                arm_alarm(cr, state);
+            }
+        });
+    });
+}
+
+impl<T: 'static> OCamlCell<T> {
+    /// Registers `f` to be called with the value when it becomes unreachable (`Gc.finalise`).
+    ///
+    /// The value is alive again when `f` is called.
+    ///
+    /// # Note
+    ///
+    /// Requires the `ocaml_interop` OCaml library (in the `ocaml/` directory of this repository)
+    /// to be linked into the program.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the value is not allocated in the OCaml heap.
+    pub fn finalise<F>(&self, cr: &mut OCamlRuntime, f: F)
+    where
+        F: FnOnce(&mut OCamlRuntime, OCamlRef<T>) + 'static,
+    {
+        ocaml_closure_reference!(gc_finalise, ocaml_interop_gc_finalise);
+        let f = Cell::new(Some(f));
+        ocaml_frame!(cr, (finaliser_root), {
+            let finaliser = OCaml::<OCamlFunction<T, ()>>::of_rust_closure(cr, move |cr, value| {
+                if let Some(f) = f.take() {
+                    f(cr, value);
+                }
+                OCaml::unit()
+            });
+            let finaliser = finaliser_root.keep(finaliser);
+            let _: OCaml<()> = gc_finalise.call2(cr, finaliser, self);
+        });
+    }
+
+    /// Reports to the GC that `bytes` of memory outside of the OCaml heap are owned by this value,
+    /// until it gets collected (`caml_alloc_dependent_memory`).
+    ///
+    /// The GC will collect more often to account for the additional memory.
+    ///
+    /// # Note
+    ///
+    /// Requires the `ocaml_interop` OCaml library (in the `ocaml/` directory of this repository)
+    /// to be linked into the program.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the value is not allocated in the OCaml heap.
+    pub fn account_external_memory(&self, cr: &mut OCamlRuntime, bytes: usize) {
+        self.finalise_last(cr, move |_cr| unsafe { caml_free_dependent_memory(bytes) });
+        unsafe { caml_alloc_dependent_memory(bytes) };
+    }
+
+    /// Registers `f` to be called when the value becomes unreachable (`Gc.finalise_last`).
+    ///
+    /// Unlike with [`finalise`](OCamlCell::finalise), the value is not accessible from `f`.
+    ///
+    /// # Note
+    ///
+    /// Requires the `ocaml_interop` OCaml library (in the `ocaml/` directory of this repository)
+    /// to be linked into the program.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the value is not allocated in the OCaml heap.
+    pub fn finalise_last<F>(&self, cr: &mut OCamlRuntime, f: F)
+    where
+        F: FnOnce(&mut OCamlRuntime) + 'static,
+    {
+        ocaml_closure_reference!(gc_finalise_last, ocaml_interop_gc_finalise_last);
+        let f = Cell::new(Some(f));
+        ocaml_frame!(cr, (finaliser_root), {
+            let finaliser = OCaml::<OCamlFunction<(), ()>>::of_rust_closure(cr, move |cr, _| {
+                if let Some(f) = f.take() {
+                    f(cr);
+                }
+                OCaml::unit()
+            });
+            let finaliser = finaliser_root.keep(finaliser);
+            let _: OCaml<()> = gc_finalise_last.call2(cr, finaliser, self);
+        });
+    }
+}
+
+unsafe fn int_field(block: RawOCaml, i: usize) -> usize {
+    int_val(*field_val(block, i)) as usize
+}
+
+unsafe fn float_field(block: RawOCaml, i: usize) -> f64 {
+    *(*field_val(block, i) as *const f64)
+}
+
+unsafe fn read_stat(stat: RawOCaml) -> OCamlGcStat {
+    OCamlGcStat {
+        minor_words: float_field(stat, 0),
+        promoted_words: float_field(stat, 1),
+        major_words: float_field(stat, 2),
+        minor_collections: int_field(stat, 3),
+        major_collections: int_field(stat, 4),
+        heap_words: int_field(stat, 5),
+        heap_chunks: int_field(stat, 6),
+        live_words: int_field(stat, 7),
+        live_blocks: int_field(stat, 8),
+        free_words: int_field(stat, 9),
+        free_blocks: int_field(stat, 10),
+        largest_free: int_field(stat, 11),
+        fragments: int_field(stat, 12),
+        compactions: int_field(stat, 13),
+        top_heap_words: int_field(stat, 14),
+        stack_size: int_field(stat, 15),
+        forced_major_collections: if wosize_val(stat) > 16 {
+            Some(int_field(stat, 16))
+        } else {
+            None
+        },
+    }
+}
@@ -0,0 +1,129 @@
+// Copyright (c) SimpleStaking and Tezedge Contributors
+// SPDX-License-Identifier: MIT
+
+use crate::{
+    conv::FromOCaml,
+    error::OCamlException,
+    memory::OCamlCell,
+    mlvalues::{is_block, tag_val, OCamlFunction, RawOCaml, UIntnat},
+    ocaml_closure_reference, ocaml_frame, OCaml, OCamlRuntime,
+};
+use core::marker::PhantomData;
+use ocaml_sys::{FORWARD, LAZY};
+
+/// `OCaml<OCamlLazy<T>>` is a reference to an OCaml `'a Lazy.t` value.
+///
+/// A lazy value is either a suspended computation, a forward block pointing to the result
+/// of the computation, or directly the result when the GC has short-circuited the forward block.
+///
+/// # Note
+///
+/// Forcing and creating lazy values requires the `ocaml_interop` OCaml library (in the `ocaml/`
+/// directory of this repository) to be linked into the program.
+pub struct OCamlLazy<A> {
+    _marker: PhantomData<A>,
+}
+
+fn is_suspended(raw: RawOCaml) -> bool {
+    if !is_block(raw) {
+        return false;
+    }
+    unsafe { tag_val(raw) == LAZY }
+}
+
+impl<'a, A> OCaml<'a, OCamlLazy<A>> {
+    /// Returns `true` if the lazy value has already been forced.
+    pub fn is_forced(&self) -> bool {
+        !is_suspended(self.raw)
+    }
+
+    /// Returns the result of the lazy value if it has already been forced, without calling
+    /// into the OCaml runtime.
+    pub fn forced_value(&self) -> Option<OCaml<'a, A>> {
+        if !self.is_forced() {
+            None
+        } else if self.is_block() && self.tag_value() == FORWARD {
+            Some(unsafe { self.field(0) })
+        } else {
+            Some(unsafe { self.unboxed() })
+        }
+    }
+}
+
+impl<A: 'static> OCamlLazy<A> {
+    /// Allocates a lazy value that calls the Rust closure `f` when forced.
+    pub fn of_rust_closure<F>(cr: &mut OCamlRuntime, f: F) -> OCaml<'_, OCamlLazy<A>>
+    where
+        F: for<'b> Fn(&'b mut OCamlRuntime) -> OCaml<'b, A> + 'static,
+    {
+        ocaml_closure_reference!(from_fun, ocaml_interop_lazy_from_fun);
+        ocaml_frame!(cr, (thunk), {
+            let thunk_fn = OCaml::<OCamlFunction<(), A>>::of_rust_closure(cr, move |cr, _| f(cr));
+            let thunk = thunk.keep(thunk_fn);
+            from_fun.call(cr, thunk)
+        })
+    }
+}
+
+impl<A> OCamlCell<OCamlLazy<A>> {
+    /// Returns `true` if the lazy value has already been forced.
+    pub fn is_forced(&self) -> bool {
+        !is_suspended(unsafe { self.get_raw() })
+    }
+
+    /// Forces the lazy value, like `Lazy.force` does.
+    ///
+    /// If the computation raises an exception, the exception is returned as an error,
+    /// and forcing the value again will raise the same exception.
+    pub fn force<'a>(&self, cr: &'a mut OCamlRuntime) -> Result<OCaml<'a, A>, OCamlException> {
+        if let Some(value) = cr.get(self).forced_value() {
+            let value = unsafe { value.raw() };
+            return Ok(unsafe { OCaml::new(cr, value) });
+        }
+        ocaml_closure_reference!(force, ocaml_interop_lazy_force);
+        force.try_call(cr, self)
+    }
+
+    /// Forces the lazy value and converts its result into a Rust value.
+    ///
+    /// Lazy values don't implement `FromOCaml`, because forcing them runs OCaml code that may
+    /// move the values being converted, so this is how they are converted into Rust values.
+    pub fn to_rust_forced<T>(&self, cr: &mut OCamlRuntime) -> Result<T, OCamlException>
+    where
+        T: FromOCaml<A>,
+    {
+        self.force(cr).map(|value| value.to_rust())
+    }
+}
+
+impl<T> OCamlCell<T> {
+    // Forces in place the lazy value stored in the `i`th field of the block,
+    // used by the record conversion macros for fields annotated with `#[force]`.
+    #[doc(hidden)]
+    pub unsafe fn force_lazy_field(
+        &self,
+        cr: &mut OCamlRuntime,
+        i: usize,
+    ) -> Result<(), OCamlException> {
+        if !is_suspended(*(self.get_raw() as *const RawOCaml).add(i)) {
+            return Ok(());
+        }
+        ocaml_frame!(cr, (lazy_root), {
+            let lazy_value: OCaml<OCamlLazy<()>> = cr.get(self).field(i as UIntnat);
+            let lazy_value = lazy_root.keep(lazy_value);
+            lazy_value.force(cr).map(|_| ())
+        })
+    }
+}
+
+// Converts the result of a lazy value that has been forced with `force_lazy_field`,
+// for the record conversion macros.
+#[doc(hidden)]
+pub fn forced_to_rust<A, T>(v: OCaml<OCamlLazy<A>>) -> T
+where
+    T: FromOCaml<A>,
+{
+    v.forced_value()
+        .expect("OCaml lazy value has to be forced before converting it")
+        .to_rust()
+}
@@ -20,10 +20,13 @@
 //!   * [Converting between OCaml and Rust data](#converting-between-ocaml-and-rust-data)
 //!     + [`FromOCaml` trait](#fromocaml-trait)
 //!     + [`ToOCaml` trait](#toocaml-trait)
+//!     + [Integer conversions](#integer-conversions)
+//!     + [Zarith](#zarith)
 //!   * [Calling convention](#calling-convention)
 //!   * [OCaml exceptions](#ocaml-exceptions)
 //!   * [Calling into OCaml from Rust](#calling-into-ocaml-from-rust)
 //!   * [Calling into Rust from OCaml](#calling-into-rust-from-ocaml)
+//!   * [Calling into OCaml from async code](#calling-into-ocaml-from-async-code)
 //! - [References and links](#references-and-links)
 //!
 //! ## How does it work
@@ -114,6 +117,14 @@
 //!
 //! A more convenient way to convert Rust values into OCaml values is provided by the [`to_ocaml!`] macro that accepts a root variable as an optional third argument to return a root containing the value.
 //!
+//! #### Integer conversions
+//!
+//! Rust integers are converted into OCaml fixnums ([`OCamlInt`]) with a checked overflow policy: a panic with an [`OCamlFixnumConversionError`] message happens when the value doesn't fit. Wrapping integers in `std::num::Wrapping` or `std::num::Saturating` selects a wrapping or saturating policy instead, and the same applies when converting OCaml ints into narrower Rust integers. [`TryToOCaml::try_to_ocaml`] is a non-panicking alternative that returns the error, as is [`OCaml::of_i128`]. Converting a `char` into an [`OCamlChar`] panics in the same way for characters outside of the Latin-1 range, and `try_to_ocaml` returns the error instead.
+//!
+//! #### Zarith
+//!
+//! With the `zarith` cargo feature, Zarith's `Z.t` (`OCamlZ`) and `Q.t` (`OCamlQ`) values can be converted from and into `num_bigint::BigInt`/`BigUint` and `num_rational::BigRational` values. Conversions copy the limbs of the numbers directly, without going through their decimal representation. Zarith has to be linked into the program.
+//!
 //! ### Calling convention
 //!
 //! There are two possible calling conventions in regards to rooting, one with *callee rooted arguments*, and another with *caller rooted arguments*.
@@ -335,6 +346,38 @@
 //! external rust_increment_bytes: bytes -> int -> bytes = "rust_increment_bytes"
 //! ```
 //!
+//! ### Calling into OCaml from async code
+//!
+//! Blocking on the OCaml runtime from inside an async executor stalls the executor's threads.
+//! Instead, the OCaml runtime can be owned by a dedicated thread, started with [`OCamlRuntimeThread::spawn`].
+//! Calls are then performed through a [`OCamlRuntimeThreadHandle`], either blocking with `call`, or
+//! with `call_async`, which returns a future that can be awaited from any executor.
+//!
+//! Inside a call, [`OCamlRuntime::releasing_runtime_await`] releases the OCaml runtime while
+//! waiting for a Rust future to complete.
+//!
+//! ```rust,no_run
+//! use ocaml_interop::{OCaml, OCamlRuntimeThread};
+//!
+//! mod ocaml_funcs {
+//!     use ocaml_interop::{ocaml, OCamlInt};
+//!
+//!     ocaml! {
+//!         pub fn twice(num: OCamlInt) -> OCamlInt;
+//!     }
+//! }
+//!
+//! async fn twice(runtime: &OCamlRuntimeThread, num: i64) -> i64 {
+//!     runtime
+//!         .handle()
+//!         .call_async(move |cr| {
+//!             let num = unsafe { OCaml::of_i64_unchecked(num) };
+//!             ocaml_funcs::twice(cr, &num).to_rust()
+//!         })
+//!         .await
+//! }
+//! ```
+//!
 //! ## References and links
 //!
 //! - OCaml Manual: [Chapter 20  Interfacing C with OCaml](https://caml.inria.fr/pub/docs/manual-ocaml/intfc.html).
@@ -345,27 +388,55 @@
 //! - [ocaml-rs](https://github.com/zshipko/ocaml-rs), another OCaml<->Rust FFI library.
 
 mod closure;
+mod collections;
+mod compare;
+mod completion;
 mod conv;
 mod error;
+mod gc;
+mod lazy;
+mod lwt;
 mod macros;
 mod memory;
 mod mlvalues;
+mod named_value;
+mod object;
 mod runtime;
+mod runtime_thread;
 mod value;
+mod weak;
+#[cfg(feature = "zarith")]
+mod zarith;
 
 pub use crate::closure::{OCamlFn1, OCamlFn2, OCamlFn3, OCamlFn4, OCamlFn5};
-pub use crate::conv::{FromOCaml, ToOCaml};
-pub use crate::error::OCamlException;
-pub use crate::memory::OCamlRef;
+pub use crate::collections::{OCamlHashtblIter, OCamlMapIter, OCamlSetIter};
+pub use crate::compare::OCamlKey;
+pub use crate::conv::{FromOCaml, FromOCamlBorrowed, ToOCaml, TryToOCaml};
+pub use crate::error::{
+    OCamlException, OCamlFixnumConversionError, OCamlFunctionNotRegistered, OCamlLwtRejection,
+    OCamlRuntimeInitError,
+};
+pub use crate::gc::{OCamlGc, OCamlGcAlarm, OCamlGcControl, OCamlGcCounters, OCamlGcStat};
+pub use crate::lazy::OCamlLazy;
+pub use crate::lwt::{OCamlLwt, OCamlLwtFuture, OCamlLwtResolver};
+pub use crate::memory::{OCamlGlobalRoot, OCamlRef};
 pub use crate::mlvalues::{
-    OCamlBytes, OCamlFloat, OCamlInt, OCamlInt32, OCamlInt64, OCamlList, RawOCaml,
+    DynBox, OCamlBytes, OCamlChar, OCamlEphemeron, OCamlFloat, OCamlFunction, OCamlHashtbl,
+    OCamlInt, OCamlInt32, OCamlInt64, OCamlList, OCamlMap, OCamlNativeint, OCamlRefCell, OCamlSet,
+    OCamlUchar, OCamlWeak, RawOCaml,
 };
-pub use crate::runtime::OCamlRuntime;
-pub use crate::value::OCaml;
+pub use crate::named_value::OCamlNamedValue;
+pub use crate::object::OCamlObject;
+pub use crate::runtime::{OCamlAllocationPolicy, OCamlRuntime, OCamlRuntimeBuilder};
+pub use crate::runtime_thread::{OCamlCall, OCamlRuntimeThread, OCamlRuntimeThreadHandle};
+pub use crate::value::{OCaml, OCamlListIter, OCamlTupleElement};
+#[cfg(feature = "zarith")]
+pub use crate::zarith::{OCamlQ, OCamlZ};
 
 #[doc(hidden)]
 pub mod internal {
-    pub use crate::closure::OCamlClosure;
+    pub use crate::closure::{OCamlClosure, OCamlClosureSlot, OCamlNamedSlot};
+    pub use crate::lazy::forced_to_rust;
     pub use crate::memory::{caml_alloc, store_field, OCamlRawRoot};
     pub use crate::mlvalues::tag;
     pub use crate::mlvalues::UNIT;
@@ -375,6 +446,14 @@ pub mod internal {
     pub fn int_val(val: super::RawOCaml) -> isize {
         unsafe { ocaml_sys::int_val(val) }
     }
+
+    // Location of the value registered with `Callback.register` under `name`, null if none
+    pub fn named_value(name: &str) -> *const super::RawOCaml {
+        match std::ffi::CString::new(name) {
+            Ok(name) => unsafe { ocaml_sys::caml_named_value(name.as_ptr()) },
+            Err(_) => std::ptr::null(),
+        }
+    }
 }
 
 #[doc(hidden)]
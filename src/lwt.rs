@@ -0,0 +1,191 @@
+// Copyright (c) SimpleStaking and Tezedge Contributors
+// SPDX-License-Identifier: MIT
+
+use crate::{
+    completion::Completion,
+    conv::{FromOCaml, ToOCaml},
+    error::{OCamlException, OCamlLwtRejection},
+    memory::{OCamlCell, OCamlGlobalRoot},
+    mlvalues::OCamlFunction,
+    ocaml_closure_reference, ocaml_frame, to_ocaml, OCaml, OCamlRef, OCamlRuntime,
+};
+use core::{
+    future::Future,
+    marker::PhantomData,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use std::sync::{Arc, Mutex};
+
+/// `OCaml<OCamlLwt<T>>` is a reference to an OCaml `'a Lwt.t` promise.
+///
+/// # Note
+///
+/// Requires the `ocaml_interop` and `ocaml_interop_lwt` OCaml libraries (in the `ocaml/`
+/// directory of this repository) to be linked into the program.
+pub struct OCamlLwt<A> {
+    _marker: PhantomData<A>,
+}
+
+// `OCaml<OCamlLwtWakener>` is a reference to an OCaml `'a Lwt.u` value.
+struct OCamlLwtWakener {}
+
+// `OCaml<OCamlExn>` is a reference to an OCaml `exn` value.
+struct OCamlExn {}
+
+impl<A: 'static> OCamlLwt<A> {
+    /// Creates a new pending promise, and a resolver that can be used to resolve it later.
+    pub fn pending(cr: &mut OCamlRuntime) -> (OCaml<'_, OCamlLwt<A>>, OCamlLwtResolver<A>) {
+        release_abandoned_resolvers(cr);
+        ocaml_closure_reference!(wait, ocaml_interop_lwt_wait);
+        let pair: OCaml<(OCamlLwt<A>, OCamlLwtWakener)> = wait.call(cr, &OCaml::unit());
+        let resolver = OCamlLwtResolver {
+            wakener: Some(OCamlGlobalRoot::new(pair.snd())),
+            _marker: PhantomData,
+        };
+        (pair.fst(), resolver)
+    }
+}
+
+impl<A: 'static> OCamlCell<OCamlLwt<A>> {
+    /// Returns a future that completes when this promise gets resolved or rejected.
+    ///
+    /// When the promise is resolved, its value is converted into a Rust value of type `R`.
+    ///
+    /// # Note
+    ///
+    /// The future is completed by the OCaml runtime when the promise gets resolved,
+    /// Lwt's scheduler has to be running for promises that depend on I/O to make progress.
+    pub fn to_future<R>(&self, cr: &mut OCamlRuntime) -> OCamlLwtFuture<R>
+    where
+        R: FromOCaml<A> + Send + 'static,
+    {
+        release_abandoned_resolvers(cr);
+        let completion = Arc::new(Completion::new());
+        let on_resolved_completion = completion.clone();
+        let on_rejected_completion = completion.clone();
+        ocaml_closure_reference!(on_any, ocaml_interop_lwt_on_any);
+        ocaml_frame!(cr, (on_resolved, on_rejected), {
+            let on_resolved_fn =
+                OCaml::<OCamlFunction<A, ()>>::of_rust_closure(cr, move |cr, value| {
+                    on_resolved_completion.complete(Ok(value.to_rust(cr)));
+                    OCaml::unit()
+                });
+            let on_resolved = on_resolved.keep(on_resolved_fn);
+            let on_rejected_fn =
+                OCaml::<OCamlFunction<OCamlExn, ()>>::of_rust_closure(cr, move |_cr, exn| {
+                    let message = unsafe { OCamlException::of(exn.get_raw()) }.message();
+                    on_rejected_completion.complete(Err(OCamlLwtRejection::new(message)));
+                    OCaml::unit()
+                });
+            let on_rejected = on_rejected.keep(on_rejected_fn);
+            let _: OCaml<()> = on_any.call3(cr, self, on_resolved, on_rejected);
+        });
+        OCamlLwtFuture { completion }
+    }
+}
+
+/// Future returned by `OCamlRef<OCamlLwt<T>>::to_future`.
+pub struct OCamlLwtFuture<R> {
+    completion: Arc<Completion<Result<R, OCamlLwtRejection>>>,
+}
+
+impl<R> Future for OCamlLwtFuture<R> {
+    type Output = Result<R, OCamlLwtRejection>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        self.completion.poll(cx)
+    }
+}
+
+/// Resolves a promise created with [`OCamlLwt::pending`].
+///
+/// Resolvers can be sent to other threads, but resolving the promise requires access to the
+/// OCaml runtime. When a resolver is dropped without being used, its promise gets rejected
+/// the next time the OCaml runtime performs an Lwt operation through this library, when
+/// [`OCamlRuntime::reject_abandoned_lwt_resolvers`] is called, or when the runtime handle
+/// is dropped. Until then the resolver's global root keeps the promise alive.
+pub struct OCamlLwtResolver<A> {
+    wakener: Option<OCamlGlobalRoot<OCamlLwtWakener>>,
+    _marker: PhantomData<A>,
+}
+
+// The global root is only accessed by the methods that take the OCaml runtime handle,
+// and when dropped the root is handed to the OCaml runtime instead of being released.
+unsafe impl<A> Send for OCamlLwtResolver<A> {}
+
+impl<A: 'static> OCamlLwtResolver<A> {
+    /// Resolves the promise with `value`.
+    pub fn resolve<T: ToOCaml<A>>(mut self, cr: &mut OCamlRuntime, value: &T) {
+        release_abandoned_resolvers(cr);
+        let wakener = self.wakener.take().unwrap();
+        ocaml_closure_reference!(resolve, ocaml_interop_lwt_resolve);
+        ocaml_frame!(cr, (value_root), {
+            let value = to_ocaml!(cr, value, value_root);
+            let _: OCaml<()> = resolve.call2(cr, wakener.as_ref(), value);
+        });
+    }
+
+    /// Rejects the promise with a `Failure message` exception.
+    pub fn reject(mut self, cr: &mut OCamlRuntime, message: &str) {
+        release_abandoned_resolvers(cr);
+        let wakener = self.wakener.take().unwrap();
+        reject_wakener(cr, &wakener, message);
+    }
+}
+
+impl<A> Drop for OCamlLwtResolver<A> {
+    fn drop(&mut self) {
+        if let Some(wakener) = self.wakener.take() {
+            let rejection: Box<dyn FnOnce(&mut OCamlRuntime)> = Box::new(move |cr| {
+                reject_wakener(
+                    cr,
+                    &wakener,
+                    "Lwt resolver dropped before resolving the promise",
+                )
+            });
+            ABANDONED_RESOLVERS
+                .lock()
+                .unwrap()
+                .push(AbandonedResolver(rejection));
+        }
+    }
+}
+
+fn reject_wakener(
+    cr: &mut OCamlRuntime,
+    wakener: &OCamlGlobalRoot<OCamlLwtWakener>,
+    message: &str,
+) {
+    ocaml_closure_reference!(reject, ocaml_interop_lwt_reject);
+    ocaml_frame!(cr, (message_root), {
+        let message: OCamlRef<String> = to_ocaml!(cr, message, message_root);
+        let _: OCaml<()> = reject.call2(cr, wakener.as_ref(), message);
+    });
+}
+
+// Rejections of promises whose resolvers were dropped, performed later from the thread
+// running the OCaml runtime.
+struct AbandonedResolver(Box<dyn FnOnce(&mut OCamlRuntime)>);
+
+unsafe impl Send for AbandonedResolver {}
+
+static ABANDONED_RESOLVERS: Mutex<Vec<AbandonedResolver>> = Mutex::new(Vec::new());
+
+impl OCamlRuntime {
+    /// Rejects the promises of the [`OCamlLwtResolver`]s that were dropped without being used,
+    /// and releases their global roots.
+    ///
+    /// Lwt operations performed through this library already do this, programs that stop
+    /// using them can call this method to avoid keeping the promises alive.
+    pub fn reject_abandoned_lwt_resolvers(&mut self) {
+        release_abandoned_resolvers(self);
+    }
+}
+
+pub(crate) fn release_abandoned_resolvers(cr: &mut OCamlRuntime) {
+    let abandoned = core::mem::take(&mut *ABANDONED_RESOLVERS.lock().unwrap());
+    for AbandonedResolver(rejection) in abandoned {
+        rejection(cr);
+    }
+}
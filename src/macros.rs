@@ -62,6 +62,16 @@ macro_rules! ocaml_frame {
 ///
 /// Calls that raise an OCaml exception will `panic!`.
 ///
+/// The function is looked up by name on every call until it has been registered, and calls
+/// made before that will `panic!`. Every declared function comes with a module of the same name
+/// with `is_available()` and `try_resolve()` functions to check if it has been registered
+/// without panicking. Because of that module, the name of a declared function can't also be used
+/// by a module, type or trait of the same scope.
+///
+/// A `#![check_registered(pub fn name)]` line at the beginning of the block declares a function
+/// that returns the names of the functions of the block that haven't been registered, to validate
+/// them at startup.
+///
 /// # Examples
 ///
 /// ```
@@ -78,8 +88,39 @@ macro_rules! ocaml_frame {
 ///     fn bytes_concat(sep: OCamlBytes, segments: OCamlList<OCamlBytes>) -> OCamlBytes;
 /// }
 /// ```
+///
+/// Checking that the declared functions have been registered:
+///
+/// ```
+/// # use ocaml_interop::*;
+/// ocaml! {
+///     #![check_registered(pub fn missing_functions)]
+///
+///     pub fn print_endline(s: String);
+///     pub fn prerr_endline(s: String);
+/// }
+///
+/// fn check_ocaml_functions() {
+///     if !print_endline::is_available() {
+///         println!("print_endline is not registered");
+///     }
+///     for name in missing_functions() {
+///         println!("{} is not registered", name);
+///     }
+/// }
+/// ```
 #[macro_export]
 macro_rules! ocaml {
+    (#![check_registered($check_vis:vis fn $check:ident)] $($t:tt)*) => {
+        $check_vis fn $check() -> ::std::vec::Vec<&'static str> {
+            let mut missing = ::std::vec::Vec::new();
+            $crate::ocaml_missing_functions!(missing, $($t)*);
+            missing
+        }
+
+        $crate::ocaml!($($t)*);
+    };
+
     () => ();
 
     ($vis:vis fn $name:ident(
@@ -89,10 +130,12 @@ macro_rules! ocaml {
             cr: &'a mut $crate::OCamlRuntime,
             $arg: $crate::OCamlRef<$typ>,
         ) -> $crate::OCaml<'a, $crate::default_to_unit!($($rtyp)?)> {
-            $crate::ocaml_closure_reference!(closure, $name);
+            let closure = $crate::ocaml_closure_reference!(@resolve $name::CLOSURE);
             closure.call(cr, $arg)
         }
 
+        $crate::ocaml_function_module!($vis $name);
+
         $crate::ocaml!($($t)*);
     };
 
@@ -105,10 +148,12 @@ macro_rules! ocaml {
             $arg1: $crate::OCamlRef<$typ1>,
             $arg2: $crate::OCamlRef<$typ2>,
         ) -> $crate::OCaml<'a, $crate::default_to_unit!($($rtyp)?)> {
-            $crate::ocaml_closure_reference!(closure, $name);
+            let closure = $crate::ocaml_closure_reference!(@resolve $name::CLOSURE);
             closure.call2(cr, $arg1, $arg2)
         }
 
+        $crate::ocaml_function_module!($vis $name);
+
         $crate::ocaml!($($t)*);
     };
 
@@ -123,10 +168,12 @@ macro_rules! ocaml {
             $arg2: $crate::OCamlRef<$typ2>,
             $arg3: $crate::OCamlRef<$typ3>,
         ) -> $crate::OCaml<'a, $crate::default_to_unit!($($rtyp)?)> {
-            $crate::ocaml_closure_reference!(closure, $name);
+            let closure = $crate::ocaml_closure_reference!(@resolve $name::CLOSURE);
             closure.call3(cr, $arg1, $arg2, $arg3)
         }
 
+        $crate::ocaml_function_module!($vis $name);
+
         $crate::ocaml!($($t)*);
     };
 
@@ -137,10 +184,12 @@ macro_rules! ocaml {
             cr: &'a mut $crate::OCamlRuntime,
             $($arg: $crate::OCamlRef<$typ>),+
     ) -> $crate::OCaml<'a, $crate::default_to_unit!($($rtyp)?)> {
-            $crate::ocaml_closure_reference!(closure, $name);
+            let closure = $crate::ocaml_closure_reference!(@resolve $name::CLOSURE);
             closure.call_n(cr, &mut [$(unsafe { $arg.get_raw() }),+])
         }
 
+        $crate::ocaml_function_module!($vis $name);
+
         $crate::ocaml!($($t)*);
     }
 }
@@ -309,11 +358,151 @@ macro_rules! to_ocaml {
 /// Implements conversion between a Rust struct and an OCaml record.
 ///
 /// See the [`impl_to_ocaml_record!`] and [`impl_from_ocaml_record!`] macros
-/// for more details.
+/// for more details, including the `#[setter(name)]` annotation for `mutable` fields and
+/// the `#[force]` annotation for lazy fields.
+///
+/// Single-field records declared with `[@@unboxed]` are not stored in a block, and must be
+/// marked with `#[unboxed]` so that the Rust struct is mapped directly to the representation
+/// of its field. Tuple structs can be used for such records too.
+///
+/// # Examples
+///
+/// ```
+/// # use ocaml_interop::*;
+/// struct UserId(i64);
+///
+/// struct Name {
+///     value: String,
+/// }
+///
+/// // Assuming OCaml type declarations like:
+/// //
+/// //      type user_id = { id: int } [@@unboxed]
+/// //      type name = { value: string } [@@unboxed]
+///
+/// impl_conv_ocaml_record! {
+///     #[unboxed]
+///     UserId(id: OCamlInt)
+/// }
+///
+/// impl_conv_ocaml_record! {
+///     #[unboxed]
+///     Name {
+///         value: String,
+///     }
+/// }
+/// ```
+///
+/// Generic structs can be mapped to polymorphic OCaml records by listing the type parameters
+/// of both types after `impl`, with the bounds required by the conversions in a `where` clause:
+///
+/// ```
+/// # use ocaml_interop::*;
+/// # use std::marker::PhantomData;
+/// struct Page<T> {
+///     items: Vec<T>,
+///     next: Option<String>,
+/// }
+///
+/// // `OCaml<OCamlPage<T>>` is a reference to an OCaml `'a page` value
+/// struct OCamlPage<T> {
+///     _marker: PhantomData<T>,
+/// }
+///
+/// // Assuming an OCaml record declaration like:
+/// //
+/// //      type 'a page = {
+/// //          items: 'a list;
+/// //          next: string option;
+/// //      }
+///
+/// impl_conv_ocaml_record! {
+///     impl<T, OT> Page<T> => OCamlPage<OT>
+///     where
+///         T: ToOCaml<OT>,
+///         T: FromOCaml<OT>,
+///     {
+///         items: OCamlList<OT>,
+///         next: Option<String>,
+///     }
+/// }
+/// ```
 #[macro_export]
 macro_rules! impl_conv_ocaml_record {
-    ($rust_typ:ident => $ocaml_typ:ident {
+    (#[unboxed] $rust_typ:ident => $ocaml_typ:ident {
+        $field:ident : $ocaml_field_typ:ty $(=> $conv_expr:expr)? $(,)?
+    }) => {
+        $crate::impl_to_ocaml_record! {
+            #[unboxed]
+            $rust_typ => $ocaml_typ {
+                $field : $ocaml_field_typ $(=> $conv_expr)?
+            }
+        }
+
+        $crate::impl_from_ocaml_record! {
+            #[unboxed]
+            $ocaml_typ => $rust_typ {
+                $field : $ocaml_field_typ
+            }
+        }
+    };
+
+    (#[unboxed] $rust_typ:ident => $ocaml_typ:ident (
+        $field:ident : $ocaml_field_typ:ty $(,)?
+    )) => {
+        $crate::impl_to_ocaml_record! {
+            #[unboxed]
+            $rust_typ => $ocaml_typ ($field : $ocaml_field_typ)
+        }
+
+        $crate::impl_from_ocaml_record! {
+            #[unboxed]
+            $ocaml_typ => $rust_typ ($field : $ocaml_field_typ)
+        }
+    };
+
+    (#[unboxed] $both_typ:ident { $($t:tt)* }) => {
+        $crate::impl_conv_ocaml_record! {
+            #[unboxed]
+            $both_typ => $both_typ {
+                $($t)*
+            }
+        }
+    };
+
+    (#[unboxed] $both_typ:ident ( $($t:tt)* )) => {
+        $crate::impl_conv_ocaml_record! {
+            #[unboxed]
+            $both_typ => $both_typ (
+                $($t)*
+            )
+        }
+    };
+
+    (impl<$($param:tt),+> $rust_typ:ty => $ocaml_typ:ty
+        $(where $($bound_typ:ty: $bound:path),+ $(,)?)? {
         $($field:ident : $ocaml_field_typ:ty $(=> $conv_expr:expr)?),+ $(,)?
+    }) => {
+        $crate::impl_to_ocaml_record! {
+            impl<$($param),+> $rust_typ => $ocaml_typ
+            $(where $($bound_typ: $bound),+)?
+            {
+                $($field : $ocaml_field_typ $(=> $conv_expr)?),+
+            }
+        }
+
+        $crate::impl_from_ocaml_record! {
+            impl<$($param),+> $ocaml_typ => $rust_typ
+            $(where $($bound_typ: $bound),+)?
+            {
+                $($field : $ocaml_field_typ),+
+            }
+        }
+    };
+
+    ($rust_typ:ident => $ocaml_typ:ident {
+        $($(#[$attr:ident $(($attr_arg:ident))?])*
+          $field:ident : $ocaml_field_typ:ty $(=> $conv_expr:expr)?),+ $(,)?
     }) => {
         $crate::impl_to_ocaml_record! {
             $rust_typ => $ocaml_typ {
@@ -323,7 +512,7 @@ macro_rules! impl_conv_ocaml_record {
 
         $crate::impl_from_ocaml_record! {
             $ocaml_typ => $rust_typ {
-                $($field : $ocaml_field_typ),+
+                $($(#[$attr $(($attr_arg))?])* $field : $ocaml_field_typ),+
             }
         }
     };
@@ -343,20 +532,150 @@ macro_rules! impl_conv_ocaml_record {
 ///
 /// See the [`impl_to_ocaml_variant!`] and [`impl_from_ocaml_variant!`] macros
 /// for more details.
+///
+/// Variants declared with `[@@unboxed]` (a single constructor with a single argument) are not
+/// stored in a block, and must be marked with `#[unboxed]` so that the Rust enum is mapped
+/// directly to the representation of the constructor argument.
+///
+/// Constructors of GADTs (`| Int : int -> int term`) have the same representation as the
+/// constructors of regular variants, and are listed in the same way: the arguments before the
+/// `->` are the tuple payload, or the fields of the inline record (`| If : { cond: bool term;
+/// ... } -> 'a term`). Since the type index is not part of the value, a single Rust enum can be
+/// used for all the instances of the GADT.
+///
+/// # Examples
+///
+/// ```
+/// # use ocaml_interop::*;
+/// enum Meters {
+///     Meters(f64),
+/// }
+///
+/// // Assuming an OCaml type declaration like:
+/// //
+/// //      type meters = Meters of float [@@unboxed]
+///
+/// impl_conv_ocaml_variant! {
+///     #[unboxed]
+///     Meters {
+///         Meters::Meters(value: OCamlFloat),
+///     }
+/// }
+/// ```
+///
+/// Generic enums can be mapped to polymorphic OCaml variants by listing the type parameters
+/// of both types after `impl`, with the bounds required by the conversions in a `where` clause:
+///
+/// ```
+/// # use ocaml_interop::*;
+/// # use std::marker::PhantomData;
+/// enum Tree<T> {
+///     Leaf,
+///     Node(Box<Tree<T>>, T, Box<Tree<T>>),
+/// }
+///
+/// // `OCaml<OCamlTree<T>>` is a reference to an OCaml `'a tree` value
+/// struct OCamlTree<T> {
+///     _marker: PhantomData<T>,
+/// }
+///
+/// // Assuming an OCaml type declaration like:
+/// //
+/// //      type 'a tree =
+/// //        | Leaf
+/// //        | Node of 'a tree * 'a * 'a tree
+///
+/// impl_conv_ocaml_variant! {
+///     impl<T, OT> Tree<T> => OCamlTree<OT>
+///     where
+///         T: ToOCaml<OT>,
+///         T: FromOCaml<OT>,
+///     {
+///         Tree::Leaf,
+///         Tree::Node(left: OCamlTree<OT>, value: OT, right: OCamlTree<OT>),
+///     }
+/// }
+/// ```
 #[macro_export]
 macro_rules! impl_conv_ocaml_variant {
+    (#[unboxed] $rust_typ:ty => $ocaml_typ:ty {
+        $($t:tt)*
+    }) => {
+        $crate::impl_to_ocaml_variant! {
+            #[unboxed]
+            $rust_typ => $ocaml_typ {
+                $($t)*
+            }
+        }
+
+        $crate::impl_from_ocaml_variant! {
+            #[unboxed]
+            $ocaml_typ => $rust_typ {
+                $($t)*
+            }
+        }
+    };
+
+    (#[unboxed] $both_typ:ty {
+        $($t:tt)*
+    }) => {
+        $crate::impl_conv_ocaml_variant!{
+            #[unboxed]
+            $both_typ => $both_typ {
+                $($t)*
+            }
+        }
+    };
+
+    (impl<$($param:tt),+> $rust_typ:ty => $ocaml_typ:ty
+        $(where $($bound_typ:ty: $bound:path),+ $(,)?)? {
+        $($($tag:ident)::+
+            $(($($slot_name:ident: $slot_typ:ty),+ $(,)?))?
+            $({$($field_name:ident: $field_typ:ty),+ $(,)?})?
+            $(=> $conv:expr)?),+ $(,)?
+    }) => {
+        $crate::impl_to_ocaml_variant! {
+            impl<$($param),+> $rust_typ => $ocaml_typ
+            $(where $($bound_typ: $bound),+)?
+            {
+                $($($tag)::+
+                    $(($($slot_name: $slot_typ),+))?
+                    $({$($field_name: $field_typ),+})?
+                    $(=> $conv)?),+
+            }
+        }
+
+        $crate::impl_from_ocaml_variant! {
+            impl<$($param),+> $ocaml_typ => $rust_typ
+            $(where $($bound_typ: $bound),+)?
+            {
+                $($($tag)::+
+                    $(($($slot_name: $slot_typ),+))?
+                    $({$($field_name: $field_typ),+})?),+
+            }
+        }
+    };
+
     ($rust_typ:ty => $ocaml_typ:ty {
-        $($($tag:ident)::+ $(($($slot_name:ident: $slot_typ:ty),+ $(,)?))? $(=> $conv:expr)?),+ $(,)?
+        $($($tag:ident)::+
+            $(($($slot_name:ident: $slot_typ:ty),+ $(,)?))?
+            $({$($field_name:ident: $field_typ:ty),+ $(,)?})?
+            $(=> $conv:expr)?),+ $(,)?
     }) => {
         $crate::impl_to_ocaml_variant! {
             $rust_typ => $ocaml_typ {
-                $($($tag)::+ $(($($slot_name: $slot_typ),+))? $(=> $conv)?),+
+                $($($tag)::+
+                    $(($($slot_name: $slot_typ),+))?
+                    $({$($field_name: $field_typ),+})?
+                    $(=> $conv)?),+
             }
         }
 
         $crate::impl_from_ocaml_variant! {
             $ocaml_typ => $rust_typ {
-                $($($tag)::+ $(($($slot_name: $slot_typ),+))?),+
+                $($($tag)::+
+                    $(($($slot_name: $slot_typ),+))?
+                    $({$($field_name: $field_typ),+})?),+
             }
         }
     };
@@ -567,63 +886,325 @@ macro_rules! ocaml_alloc_record {
 ///     }
 /// }
 /// ```
+///
+/// Fields that are declared `mutable` in OCaml can be annotated with `#[setter(name)]`, to
+/// generate an associated function `name(cr, record, value)` of the OCaml type that replaces
+/// the value of the field in place, respecting OCaml's write barrier:
+///
+/// ```
+/// # use ocaml_interop::*;
+/// struct Counter {
+///     name: String,
+///     count: i64,
+/// }
+///
+/// // Assuming an OCaml record declaration like:
+/// //
+/// //      type counter = {
+/// //          name: string;
+/// //          mutable count: int;
+/// //      }
+///
+/// impl_from_ocaml_record! {
+///     Counter {
+///         name: String,
+///         #[setter(set_count)]
+///         count: OCamlInt,
+///     }
+/// }
+///
+/// # fn record_setters_example(cr: &mut OCamlRuntime, counter: OCamlRef<Counter>) {
+/// let count = OCaml::of_i32(10);
+/// Counter::set_count(cr, counter, &count);
+/// # }
+/// ```
+///
+/// Fields of type [`OCamlLazy`] can be annotated with `#[force]` to convert the result of the
+/// lazy value, whatever its type. Forcing runs OCaml code, so it can't happen during a
+/// [`FromOCaml`] conversion: instead of implementing [`FromOCaml`], the macro generates the
+/// associated functions `to_rust_forced(cr, record)`, which forces the annotated fields in place
+/// and converts the record, and `force_lazy_fields(cr, record)` of the OCaml type.
+///
+/// ```
+/// # use ocaml_interop::*;
+/// struct Settings {
+///     name: String,
+///     tags: Vec<String>,
+/// }
+///
+/// // Assuming an OCaml record declaration like:
+/// //
+/// //      type settings = {
+/// //          name: string;
+/// //          tags: string list Lazy.t;
+/// //      }
+///
+/// impl_from_ocaml_record! {
+///     Settings {
+///         name: String,
+///         #[force]
+///         tags: OCamlLazy<OCamlList<String>>,
+///     }
+/// }
+///
+/// # fn record_force_example(cr: &mut OCamlRuntime, settings: OCamlRef<Settings>) {
+/// let settings: Settings = Settings::to_rust_forced(cr, settings).unwrap();
+/// # }
+/// ```
+///
+/// Records with a lifetime parameter implement [`FromOCamlBorrowed`] instead, and their fields
+/// can borrow from the OCaml value (`&'a str`, `&'a [u8]`, `Cow<'a, str>`):
+///
+/// ```
+/// # use ocaml_interop::*;
+/// # struct MyStruct {}
+/// struct MyStructRef<'a> {
+///     int_field: i64,
+///     string_field: &'a str,
+/// }
+///
+/// impl_from_ocaml_record! {
+///     MyStruct => MyStructRef<'a> {
+///         int_field: OCamlInt,
+///         string_field: String,
+///     }
+/// }
+/// ```
 #[macro_export]
 macro_rules! impl_from_ocaml_record {
-    ($ocaml_typ:ident => $rust_typ:ident {
-        $($field:ident : $ocaml_field_typ:ty),+ $(,)?
-    }) => {
+    (@accessors $ocaml_typ:ident => $rust_typ:ident, $index:expr, [], [$($fields:tt)*],) => {
         unsafe impl $crate::FromOCaml<$ocaml_typ> for $rust_typ {
             fn from_ocaml(v: $crate::OCaml<$ocaml_typ>) -> Self {
-                $crate::ocaml_unpack_record! { v =>
-                    $rust_typ {
-                        $($field : $ocaml_field_typ),+
-                    }
-                }
+                $crate::impl_from_ocaml_record! { @convert v => $rust_typ { $($fields)* } }
             }
         }
     };
 
-    ($both_typ:ident {
-        $($t:tt)*
-    }) => {
-        $crate::impl_from_ocaml_record! {
-            $both_typ => $both_typ {
-                $($t)*
+    (@accessors $ocaml_typ:ident => $rust_typ:ident, $index:expr, [$($forced:expr,)+],
+        [$($fields:tt)*],) => {
+        impl $ocaml_typ {
+            #[allow(dead_code)]
+            pub fn force_lazy_fields(
+                cr: &mut $crate::OCamlRuntime,
+                record: $crate::OCamlRef<$ocaml_typ>,
+            ) -> Result<(), $crate::OCamlException> {
+                $(
+                    unsafe { record.force_lazy_field(cr, $forced)? };
+                )+
+                Ok(())
+            }
+
+            #[allow(dead_code)]
+            pub fn to_rust_forced(
+                cr: &mut $crate::OCamlRuntime,
+                record: $crate::OCamlRef<$ocaml_typ>,
+            ) -> Result<$rust_typ, $crate::OCamlException> {
+                Self::force_lazy_fields(cr, record)?;
+                let record = cr.get(record);
+                Ok($crate::impl_from_ocaml_record! { @convert record => $rust_typ { $($fields)* } })
             }
         }
     };
 
-    ($ocaml_typ:ident => $rust_typ:ident (
-        $($field:ident : $ocaml_field_typ:ty),+ $(,)?
-    )) => {
-        unsafe impl $crate::FromOCaml<$ocaml_typ> for $rust_typ {
-            fn from_ocaml(v: $crate::OCaml<$ocaml_typ>) -> Self {
-                $crate::ocaml_unpack_record! { v =>
-                    $rust_typ (
-                        $($field : $ocaml_field_typ),+
-                    )
-                }
+    (@accessors $ocaml_typ:ident => $rust_typ:ident, $index:expr, [$($forced:expr,)*],
+        [$($fields:tt)*],
+        $field:ident : $ocaml_field_typ:ty [setter($setter:ident)] $([$($attr:tt)*])*,
+        $($rest:tt)*) => {
+        impl $ocaml_typ {
+            #[allow(dead_code)]
+            pub fn $setter(
+                cr: &mut $crate::OCamlRuntime,
+                record: $crate::OCamlRef<$ocaml_typ>,
+                value: $crate::OCamlRef<$ocaml_field_typ>,
+            ) {
+                unsafe { record.set_field(cr, $index, value) }
             }
         }
+
+        $crate::impl_from_ocaml_record! {
+            @accessors $ocaml_typ => $rust_typ, $index, [$($forced,)*], [$($fields)*],
+            $field : $ocaml_field_typ $([$($attr)*])*, $($rest)*
+        }
     };
 
-    ($both_typ:ident (
-        $($t:tt)*
-    )) => {
+    (@accessors $ocaml_typ:ident => $rust_typ:ident, $index:expr, [$($forced:expr,)*],
+        [$($fields:tt)*],
+        $field:ident : $ocaml_field_typ:ty [force] $([$($attr:tt)*])*, $($rest:tt)*) => {
         $crate::impl_from_ocaml_record! {
-            $both_typ => $both_typ (
-                $($t)*
-            )
+            @accessors $ocaml_typ => $rust_typ, $index, [$($forced,)* $index,], [$($fields)*],
+            $field : $ocaml_field_typ $([$($attr)*])*, $($rest)*
         }
     };
-}
 
-/// Implements [`ToOCaml`] for mapping a Rust record into an OCaml record.
-///
-/// It is important that the order of the fields remains the same as in the OCaml type declaration.
-///
-/// # Examples
-///
+    (@accessors $ocaml_typ:ident => $rust_typ:ident, $index:expr, [$($forced:expr,)*],
+        [$($fields:tt)*],
+        $field:ident : $ocaml_field_typ:ty, $($rest:tt)*) => {
+        $crate::impl_from_ocaml_record! {
+            @accessors $ocaml_typ => $rust_typ, $index + 1, [$($forced,)*], [$($fields)*],
+            $($rest)*
+        }
+    };
+
+    (@convert $record:ident => $rust_typ:ident {
+        $($field:ident : $ocaml_field_typ:ty $([$attr:ident $(($attr_arg:ident))?])*,)+
+    }) => {
+        unsafe {
+            let mut current = 0;
+
+            $(
+                let $field = $crate::impl_from_ocaml_record! {
+                    @field $record, current, $ocaml_field_typ, $($attr)*
+                };
+                current += 1;
+            )+
+
+            $rust_typ {
+                $($field),+
+            }
+        }
+    };
+
+    (@field $record:ident, $index:ident, $ocaml_field_typ:ty,) => {
+        $record.field::<$ocaml_field_typ>($index).to_rust()
+    };
+
+    (@field $record:ident, $index:ident, $ocaml_field_typ:ty, force $($attr:ident)*) => {
+        $crate::internal::forced_to_rust($record.field::<$ocaml_field_typ>($index))
+    };
+
+    (@field $record:ident, $index:ident, $ocaml_field_typ:ty, $other:ident $($attr:ident)*) => {
+        $crate::impl_from_ocaml_record! { @field $record, $index, $ocaml_field_typ, $($attr)* }
+    };
+
+    (#[unboxed] $ocaml_typ:ident => $rust_typ:ident {
+        $field:ident : $ocaml_field_typ:ty $(,)?
+    }) => {
+        unsafe impl $crate::FromOCaml<$ocaml_typ> for $rust_typ {
+            fn from_ocaml(v: $crate::OCaml<$ocaml_typ>) -> Self {
+                let $field = unsafe { v.unboxed::<$ocaml_field_typ>() }.to_rust();
+                $rust_typ { $field }
+            }
+        }
+    };
+
+    (#[unboxed] $ocaml_typ:ident => $rust_typ:ident (
+        $field:ident : $ocaml_field_typ:ty $(,)?
+    )) => {
+        unsafe impl $crate::FromOCaml<$ocaml_typ> for $rust_typ {
+            fn from_ocaml(v: $crate::OCaml<$ocaml_typ>) -> Self {
+                let $field = unsafe { v.unboxed::<$ocaml_field_typ>() }.to_rust();
+                $rust_typ($field)
+            }
+        }
+    };
+
+    (#[unboxed] $both_typ:ident { $($t:tt)* }) => {
+        $crate::impl_from_ocaml_record! {
+            #[unboxed]
+            $both_typ => $both_typ {
+                $($t)*
+            }
+        }
+    };
+
+    (#[unboxed] $both_typ:ident ( $($t:tt)* )) => {
+        $crate::impl_from_ocaml_record! {
+            #[unboxed]
+            $both_typ => $both_typ (
+                $($t)*
+            )
+        }
+    };
+
+    (impl<$($param:tt),+> $ocaml_typ:ty => $rust_typ:ty
+        $(where $($bound_typ:ty: $bound:path),+ $(,)?)? {
+        $($field:ident : $ocaml_field_typ:ty),+ $(,)?
+    }) => {
+        unsafe impl<$($param),+> $crate::FromOCaml<$ocaml_typ> for $rust_typ
+        $(where $($bound_typ: $bound),+)?
+        {
+            fn from_ocaml(v: $crate::OCaml<$ocaml_typ>) -> Self {
+                $crate::ocaml_unpack_record! { v =>
+                    Self {
+                        $($field : $ocaml_field_typ),+
+                    }
+                }
+            }
+        }
+    };
+
+    ($ocaml_typ:ident => $rust_typ:ident {
+        $($(#[$attr:ident $(($attr_arg:ident))?])* $field:ident : $ocaml_field_typ:ty),+ $(,)?
+    }) => {
+        $crate::impl_from_ocaml_record! {
+            @accessors $ocaml_typ => $rust_typ, 0usize, [],
+            [$($field : $ocaml_field_typ $([$attr $(($attr_arg))?])*,)+],
+            $($field : $ocaml_field_typ $([$attr $(($attr_arg))?])*,)+
+        }
+    };
+
+    ($both_typ:ident {
+        $($t:tt)*
+    }) => {
+        $crate::impl_from_ocaml_record! {
+            $both_typ => $both_typ {
+                $($t)*
+            }
+        }
+    };
+
+    ($ocaml_typ:ident => $rust_typ:ident (
+        $($field:ident : $ocaml_field_typ:ty),+ $(,)?
+    )) => {
+        unsafe impl $crate::FromOCaml<$ocaml_typ> for $rust_typ {
+            fn from_ocaml(v: $crate::OCaml<$ocaml_typ>) -> Self {
+                $crate::ocaml_unpack_record! { v =>
+                    $rust_typ (
+                        $($field : $ocaml_field_typ),+
+                    )
+                }
+            }
+        }
+    };
+
+    ($both_typ:ident (
+        $($t:tt)*
+    )) => {
+        $crate::impl_from_ocaml_record! {
+            $both_typ => $both_typ (
+                $($t)*
+            )
+        }
+    };
+
+    ($ocaml_typ:ident => $rust_typ:ident<$lt:lifetime> {
+        $($field:ident : $ocaml_field_typ:ty),+ $(,)?
+    }) => {
+        unsafe impl<$lt> $crate::FromOCamlBorrowed<$lt, $ocaml_typ> for $rust_typ<$lt> {
+            fn from_ocaml_borrowed(v: $crate::OCaml<$lt, $ocaml_typ>) -> Self {
+                unsafe {
+                    let mut current = 0;
+
+                    $(
+                        let $field = v.field::<$ocaml_field_typ>(current).to_rust_borrowed();
+                        current += 1;
+                    )+
+
+                    $rust_typ {
+                        $($field),+
+                    }
+                }
+            }
+        }
+    };
+}
+
+/// Implements [`ToOCaml`] for mapping a Rust record into an OCaml record.
+///
+/// It is important that the order of the fields remains the same as in the OCaml type declaration.
+///
+/// # Examples
+///
 /// ```
 /// # use ocaml_interop::*;
 /// struct MyStruct {
@@ -655,6 +1236,65 @@ macro_rules! impl_from_ocaml_record {
 /// ```
 #[macro_export]
 macro_rules! impl_to_ocaml_record {
+    (#[unboxed] $rust_typ:ident => $ocaml_typ:ident {
+        $field:ident : $ocaml_field_typ:ty $(=> $conv_expr:expr)? $(,)?
+    }) => {
+        unsafe impl $crate::ToOCaml<$ocaml_typ> for $rust_typ {
+            fn to_ocaml<'a>(&self, cr: &'a mut $crate::OCamlRuntime) -> $crate::OCaml<'a, $ocaml_typ> {
+                let $field = &$crate::prepare_field_for_mapping!(self.$field $(=> $conv_expr)?);
+                let $field: $crate::OCaml<$ocaml_field_typ> = $crate::to_ocaml!(cr, $field);
+                unsafe { $field.unboxed() }
+            }
+        }
+    };
+
+    (#[unboxed] $rust_typ:ident => $ocaml_typ:ident (
+        $field:ident : $ocaml_field_typ:ty $(,)?
+    )) => {
+        unsafe impl $crate::ToOCaml<$ocaml_typ> for $rust_typ {
+            fn to_ocaml<'a>(&self, cr: &'a mut $crate::OCamlRuntime) -> $crate::OCaml<'a, $ocaml_typ> {
+                let $rust_typ($field) = self;
+                let $field: $crate::OCaml<$ocaml_field_typ> = $crate::to_ocaml!(cr, $field);
+                unsafe { $field.unboxed() }
+            }
+        }
+    };
+
+    (#[unboxed] $both_typ:ident { $($t:tt)* }) => {
+        $crate::impl_to_ocaml_record! {
+            #[unboxed]
+            $both_typ => $both_typ {
+                $($t)*
+            }
+        }
+    };
+
+    (#[unboxed] $both_typ:ident ( $($t:tt)* )) => {
+        $crate::impl_to_ocaml_record! {
+            #[unboxed]
+            $both_typ => $both_typ (
+                $($t)*
+            )
+        }
+    };
+
+    (impl<$($param:tt),+> $rust_typ:ty => $ocaml_typ:ty
+        $(where $($bound_typ:ty: $bound:path),+ $(,)?)? {
+        $($field:ident : $ocaml_field_typ:ty $(=> $conv_expr:expr)?),+ $(,)?
+    }) => {
+        unsafe impl<$($param),+> $crate::ToOCaml<$ocaml_typ> for $rust_typ
+        $(where $($bound_typ: $bound),+)?
+        {
+            fn to_ocaml<'a>(&self, cr: &'a mut $crate::OCamlRuntime) -> $crate::OCaml<'a, $ocaml_typ> {
+                $crate::ocaml_alloc_record! {
+                    cr, self {
+                        $($field : $ocaml_field_typ $(=> $conv_expr)?),+
+                    }
+                }
+            }
+        }
+    };
+
     ($rust_typ:ty => $ocaml_typ:ident {
         $($field:ident : $ocaml_field_typ:ty $(=> $conv_expr:expr)?),+ $(,)?
     }) => {
@@ -692,6 +1332,7 @@ macro_rules! impl_to_ocaml_record {
 ///     StepLeft,
 ///     StepRight,
 ///     Rotate(f64),
+///     Jump { dx: i64, dy: i64 },
 /// }
 ///
 /// // Assuming an OCaml type declaration like:
@@ -700,6 +1341,7 @@ macro_rules! impl_to_ocaml_record {
 /// //        | StepLeft
 /// //        | StepRight
 /// //        | Rotate of float
+/// //        | Jump of { dx: int; dy: int }
 /// //
 /// // NOTE: What is important is the order of the tags, not their names.
 ///
@@ -713,11 +1355,69 @@ macro_rules! impl_to_ocaml_record {
 ///         Movement::StepRight,
 ///         // Tag field names are mandatory
 ///         Movement::Rotate(rotation: OCamlFloat),
+///         // Inline records, fields must be in the same order as in the OCaml declaration
+///         Movement::Jump { dx: OCamlInt, dy: OCamlInt },
 ///     }
 /// }
 /// ```
 #[macro_export]
 macro_rules! impl_from_ocaml_variant {
+    (#[unboxed] $ocaml_typ:ty => $rust_typ:ty {
+        $($tag:ident)::+ ($slot_name:ident: $slot_typ:ty) $(,)?
+    }) => {
+        unsafe impl $crate::FromOCaml<$ocaml_typ> for $rust_typ {
+            fn from_ocaml(v: $crate::OCaml<$ocaml_typ>) -> Self {
+                let $slot_name = unsafe { v.unboxed::<$slot_typ>() }.to_rust();
+                $($tag)::+($slot_name)
+            }
+        }
+    };
+
+    (#[unboxed] $ocaml_typ:ty => $rust_typ:ty {
+        $($tag:ident)::+ {$slot_name:ident: $slot_typ:ty $(,)?} $(,)?
+    }) => {
+        unsafe impl $crate::FromOCaml<$ocaml_typ> for $rust_typ {
+            fn from_ocaml(v: $crate::OCaml<$ocaml_typ>) -> Self {
+                let $slot_name = unsafe { v.unboxed::<$slot_typ>() }.to_rust();
+                $($tag)::+ { $slot_name }
+            }
+        }
+    };
+
+    (#[unboxed] $both_typ:ty {
+        $($t:tt)*
+    }) => {
+        $crate::impl_from_ocaml_variant!{
+            #[unboxed]
+            $both_typ => $both_typ {
+                $($t)*
+            }
+        }
+    };
+
+    (impl<$($param:tt),+> $ocaml_typ:ty => $rust_typ:ty
+        $(where $($bound_typ:ty: $bound:path),+ $(,)?)? {
+        $($t:tt)*
+    }) => {
+        unsafe impl<$($param),+> $crate::FromOCaml<$ocaml_typ> for $rust_typ
+        $(where $($bound_typ: $bound),+)?
+        {
+            fn from_ocaml(v: $crate::OCaml<$ocaml_typ>) -> Self {
+                let result = $crate::ocaml_unpack_variant! {
+                    v => {
+                        $($t)*
+                    }
+                };
+
+                let msg = concat!(
+                    "Failure when unpacking an OCaml<", stringify!($ocaml_typ), "> variant into ",
+                    stringify!($rust_typ), " (unexpected tag value)");
+
+                result.expect(msg)
+            }
+        }
+    };
+
     ($ocaml_typ:ty => $rust_typ:ty {
         $($t:tt)*
     }) => {
@@ -795,33 +1495,30 @@ macro_rules! impl_from_ocaml_variant {
 #[macro_export]
 macro_rules! ocaml_unpack_variant {
     ($self:ident => {
-        $($($tag:ident)::+ $(($($slot_name:ident: $slot_typ:ty),+ $(,)?))? $(=> $conv:expr)?),+ $(,)?
+        $($($tag:ident)::+
+            $(($($slot_name:ident: $slot_typ:ty),+ $(,)?))?
+            $({$($field_name:ident: $field_typ:ty),+ $(,)?})?
+            $(=> $conv:expr)?),+ $(,)?
     }) => {
         (|| {
-            let mut current_block_tag = 0;
-            let mut current_long_tag = 0;
-
-            $(
-                $crate::unpack_variant_tag!(
-                    $self, current_block_tag, current_long_tag,
-                    $($tag)::+ $(($($slot_name: $slot_typ),+))? $(=> $conv)?);
-            )+
-
-            Err("Invalid tag value found when converting from an OCaml variant")
-        })()
-    };
+            // OCaml variants can't have more than 246 non-constant constructors,
+            // higher tag values are reserved for blocks with a special meaning.
+            const _: () = assert!(
+                0usize $($(+ ([$(stringify!($slot_name)),+].len() > 0) as usize)?
+                    $(+ ([$(stringify!($field_name)),+].len() > 0) as usize)?)+ <= 246,
+                "OCaml variants can't have more than 246 non-constant constructors"
+            );
 
-    ($self:ident => {
-        $($($tag:ident)::+ $({$($slot_name:ident: $slot_typ:ty),+ $(,)?})? $(=> $conv:expr)?),+ $(,)?
-    }) => {
-        (|| {
             let mut current_block_tag = 0;
             let mut current_long_tag = 0;
 
             $(
                 $crate::unpack_variant_tag!(
                     $self, current_block_tag, current_long_tag,
-                    $($tag)::+ $({$($slot_name: $slot_typ),+})? $(=> $conv)?);
+                    $($tag)::+
+                        $(($($slot_name: $slot_typ),+))?
+                        $({$($field_name: $field_typ),+})?
+                        $(=> $conv)?);
             )+
 
             Err("Invalid tag value found when converting from an OCaml variant")
@@ -862,141 +1559,598 @@ macro_rules! ocaml_unpack_variant {
 ///         Movement::StepLeft,
 ///         Movement::StepRight,
 ///         // Tag field names are mandatory
-///         Movement::Rotate(rotation: OCamlFloat),
+///         Movement::Rotate(rotation: OCamlFloat),
+///     }
+/// };
+/// // ...
+/// # }
+/// ```
+#[macro_export]
+macro_rules! ocaml_alloc_variant {
+    ($cr:ident, $self:ident => {
+        $($($tag:ident)::+
+            $(($($slot_name:ident: $slot_typ:ty),+ $(,)?))?
+            $({$($field_name:ident: $field_typ:ty),+ $(,)?})?
+            $(,)?),+
+    }) => {
+        $crate::ocaml_alloc_variant_match!{
+            $cr, $self, 0u8, 0u8,
+
+            @units {}
+            @blocks {}
+            @records {}
+
+            @pending $({
+                $($tag)::+ $(($($slot_name: $slot_typ),+))? $({$($field_name: $field_typ),+})?
+            })+
+        }
+    };
+}
+
+/// Implements [`ToOCaml`] for mapping a Rust enum into an OCaml variant.
+///
+/// The match in this conversion is exhaustive, and requires that every enum case is covered.
+///
+/// It is important that the order of the fields remains the same as in the OCaml type declaration.
+///
+/// # Examples
+///
+/// ```
+/// # use ocaml_interop::*;
+/// enum Movement {
+///     StepLeft,
+///     StepRight,
+///     Rotate(f64),
+///     Jump { dx: i64, dy: i64 },
+/// }
+///
+/// // Assuming an OCaml type declaration like:
+/// //
+/// //      type movement =
+/// //        | StepLeft
+/// //        | StepRight
+/// //        | Rotate of float
+/// //        | Jump of { dx: int; dy: int }
+/// //
+/// // NOTE: What is important is the order of the tags, not their names.
+///
+/// impl_to_ocaml_variant! {
+///     // Optionally, if Rust and OCaml types don't match:
+///     // RustType => OCamlType { ... }
+///     Movement {
+///         Movement::StepLeft,
+///         Movement::StepRight,
+///         // Tag field names are mandatory
+///         Movement::Rotate(rotation: OCamlFloat),
+///         // Inline records, fields must be in the same order as in the OCaml declaration
+///         Movement::Jump { dx: OCamlInt, dy: OCamlInt },
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! impl_to_ocaml_variant {
+    (#[unboxed] $rust_typ:ty => $ocaml_typ:ty {
+        $($tag:ident)::+ ($slot_name:ident: $slot_typ:ty) $(,)?
+    }) => {
+        unsafe impl $crate::ToOCaml<$ocaml_typ> for $rust_typ {
+            fn to_ocaml<'a>(&self, cr: &'a mut $crate::OCamlRuntime) -> $crate::OCaml<'a, $ocaml_typ> {
+                let $($tag)::+($slot_name) = self;
+                let $slot_name: $crate::OCaml<$slot_typ> = $crate::to_ocaml!(cr, $slot_name);
+                unsafe { $slot_name.unboxed() }
+            }
+        }
+    };
+
+    (#[unboxed] $rust_typ:ty => $ocaml_typ:ty {
+        $($tag:ident)::+ {$slot_name:ident: $slot_typ:ty $(,)?} $(,)?
+    }) => {
+        unsafe impl $crate::ToOCaml<$ocaml_typ> for $rust_typ {
+            fn to_ocaml<'a>(&self, cr: &'a mut $crate::OCamlRuntime) -> $crate::OCaml<'a, $ocaml_typ> {
+                let $($tag)::+ { $slot_name } = self;
+                let $slot_name: $crate::OCaml<$slot_typ> = $crate::to_ocaml!(cr, $slot_name);
+                unsafe { $slot_name.unboxed() }
+            }
+        }
+    };
+
+    (#[unboxed] $both_typ:ty {
+        $($t:tt)*
+    }) => {
+        $crate::impl_to_ocaml_variant!{
+            #[unboxed]
+            $both_typ => $both_typ {
+                $($t)*
+            }
+        }
+    };
+
+    (impl<$($param:tt),+> $rust_typ:ty => $ocaml_typ:ty
+        $(where $($bound_typ:ty: $bound:path),+ $(,)?)? {
+        $($t:tt)*
+    }) => {
+        unsafe impl<$($param),+> $crate::ToOCaml<$ocaml_typ> for $rust_typ
+        $(where $($bound_typ: $bound),+)?
+        {
+            fn to_ocaml<'a>(&self, cr: &'a mut $crate::OCamlRuntime) -> $crate::OCaml<'a, $ocaml_typ> {
+                $crate::ocaml_alloc_variant! {
+                    cr, self => {
+                        $($t)*
+                    }
+                }
+            }
+        }
+    };
+
+    ($rust_typ:ty => $ocaml_typ:ty {
+        $($t:tt)*
+    }) => {
+        unsafe impl $crate::ToOCaml<$ocaml_typ> for $rust_typ {
+            fn to_ocaml<'a>(&self, cr: &'a mut $crate::OCamlRuntime) -> $crate::OCaml<'a, $ocaml_typ> {
+                $crate::ocaml_alloc_variant! {
+                    cr, self => {
+                        $($t)*
+                    }
+                }
+            }
+        }
+    };
+
+    ($both_typ:ty {
+        $($t:tt)*
+    }) => {
+        $crate::impl_to_ocaml_variant!{
+            $both_typ => $both_typ {
+                $($t)*
+            }
+        }
+    };
+}
+
+/// Implements [`FromOCaml`] for mapping an OCaml variant into a Rust enum.
+///
+/// It is important that the order of the fields remains the same as in the OCaml type declaration.
+///
+/// # Examples
+///
+/// ```
+/// # use ocaml_interop::*;
+/// enum Movement {
+///     StepLeft,
+///     StepRight,
+///     Rotate(f64),
+/// }
+///
+/// // Assuming an OCaml type declaration like:
+/// //
+/// //      type movement = [
+/// //        | `StepLeft
+/// //        | `StepRight
+/// //        | `Rotate of float
+/// //      ]
+///
+/// impl_from_ocaml_polymorphic_variant! {
+///     // Optionally, if Rust and OCaml types don't match:
+///     // OCamlType => RustType { ... }
+///     Movement {
+///         StepLeft  => Movement::StepLeft,
+///         StepRight => Movement::StepRight,
+///         // Tag field names are mandatory
+///         Rotate(rotation: OCamlFloat)
+///                   => Movement::Rotate(rotation),
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! impl_from_ocaml_polymorphic_variant {
+    ($ocaml_typ:ty => $rust_typ:ty {
+        $($t:tt)*
+    }) => {
+        unsafe impl $crate::FromOCaml<$ocaml_typ> for $rust_typ {
+            fn from_ocaml(v: $crate::OCaml<$ocaml_typ>) -> Self {
+                let result = $crate::ocaml_unpack_polymorphic_variant! {
+                    v => {
+                        $($t)*
+                    }
+                };
+
+                let msg = concat!(
+                    "Failure when unpacking an OCaml<", stringify!($ocaml_typ), "> polymorphic variant into ",
+                    stringify!($rust_typ), " (unexpected tag value)");
+
+                result.expect(msg)
+            }
+        }
+    };
+
+    ($both_typ:ty {
+        $($t:tt)*
+    }) => {
+        $crate::impl_from_ocaml_polymorphic_variant!{
+            $both_typ => $both_typ {
+                $($t)*
+            }
+        }
+    };
+}
+
+/// Unpacks an OCaml polymorphic variant and maps it into a Rust enum.
+///
+/// # Note
+///
+/// Unlike with [`ocaml_unpack_record!`], the result of [`ocaml_unpack_polymorphic_variant!`] is a `Result` value.
+/// An error will be returned in the case of an unexpected tag value. This may change in the future.
+///
+/// # Examples
+///
+/// ```
+/// # use ocaml_interop::*;
+/// # ocaml! { fn make_ocaml_polymorphic_movement(unit: ()) -> Movement; }
+/// enum Movement {
+///     StepLeft,
+///     StepRight,
+///     Rotate(f64),
+/// }
+///
+/// // Assuming an OCaml type declaration like:
+/// //
+/// //      type movement = [
+/// //        | `StepLeft
+/// //        | `StepRight
+/// //        | `Rotate of float
+/// //      ]
+///
+/// # fn unpack_polymorphic_variant_example(cr: &mut OCamlRuntime) {
+/// let ocaml_polymorphic_variant = make_ocaml_polymorphic_movement(cr, &OCaml::unit());
+/// let result = ocaml_unpack_polymorphic_variant! {
+///     ocaml_polymorphic_variant => {
+///         StepLeft  => Movement::StepLeft,
+///         StepRight => Movement::StepRight,
+///         // Tag field names are mandatory
+///         Rotate(rotation: OCamlFloat)
+///                   => Movement::Rotate(rotation),
+///     }
+/// }.unwrap();
+/// // ...
+/// # }
+#[macro_export]
+macro_rules! ocaml_unpack_polymorphic_variant {
+    ($self:ident => {
+        $($tag:ident $(($($slot_name:ident: $slot_typ:ty),+ $(,)?))? => $conv:expr),+ $(,)?
+    }) => {
+        (|| {
+            $(
+                $crate::unpack_polymorphic_variant_tag!(
+                    $self, $tag $(($($slot_name: $slot_typ),+))? => $conv);
+            )+
+
+            Err("Invalid tag value found when converting from an OCaml polymorphic variant")
+        })()
+    };
+}
+
+/// Allocates an OCaml polymorphic variant, mapped from a Rust enum.
+///
+/// The syntax is the same as the one of [`ocaml_unpack_polymorphic_variant!`], with the
+/// right-hand side of each case being a pattern that matches the Rust value and binds the
+/// tag fields. Tags without fields are allocated as immediate values, and tags with more than
+/// one field get a tuple as their payload.
+///
+/// # Examples
+///
+/// ```
+/// # use ocaml_interop::*;
+/// enum Movement {
+///     StepLeft,
+///     StepRight,
+///     Rotate(f64),
+/// }
+///
+/// // Assuming an OCaml type declaration like:
+/// //
+/// //      type movement = [
+/// //        | `StepLeft
+/// //        | `StepRight
+/// //        | `Rotate of float
+/// //      ]
+///
+/// # fn alloc_polymorphic_variant_example(cr: &mut OCamlRuntime) {
+/// let movement = Movement::Rotate(180.0);
+/// let ocaml_movement: OCaml<Movement> = ocaml_alloc_polymorphic_variant! {
+///     cr, movement => {
+///         StepLeft  => Movement::StepLeft,
+///         StepRight => Movement::StepRight,
+///         // Tag field names are mandatory
+///         Rotate(rotation: OCamlFloat)
+///                   => Movement::Rotate(rotation),
+///     }
+/// };
+/// // ...
+/// # }
+/// ```
+#[macro_export]
+macro_rules! ocaml_alloc_polymorphic_variant {
+    ($cr:ident, $self:ident => {
+        $($tag:ident $(($($slot_name:ident: $slot_typ:ty),+ $(,)?))? => $pat:pat),+ $(,)?
+    }) => {
+        match $self {
+            $(
+                $pat => $crate::alloc_polymorphic_variant_tag!(
+                    $cr, $tag $(($($slot_name: $slot_typ),+))?),
+            )+
+        }
+    };
+}
+
+/// Implements [`ToOCaml`] for mapping a Rust enum into an OCaml polymorphic variant.
+///
+/// The match in this conversion is exhaustive, and requires that every enum case is covered.
+///
+/// # Examples
+///
+/// ```
+/// # use ocaml_interop::*;
+/// enum Movement {
+///     StepLeft,
+///     StepRight,
+///     Rotate(f64),
+///     Move(f64, f64),
+/// }
+///
+/// // Assuming an OCaml type declaration like:
+/// //
+/// //      type movement = [
+/// //        | `StepLeft
+/// //        | `StepRight
+/// //        | `Rotate of float
+/// //        | `Move of float * float
+/// //      ]
+///
+/// impl_to_ocaml_polymorphic_variant! {
+///     // Optionally, if Rust and OCaml types don't match:
+///     // RustType => OCamlType { ... }
+///     Movement {
+///         StepLeft  => Movement::StepLeft,
+///         StepRight => Movement::StepRight,
+///         // Tag field names are mandatory
+///         Rotate(rotation: OCamlFloat)
+///                   => Movement::Rotate(rotation),
+///         // Tags with more than one field get a tuple as payload
+///         Move(dx: OCamlFloat, dy: OCamlFloat)
+///                   => Movement::Move(dx, dy),
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! impl_to_ocaml_polymorphic_variant {
+    ($rust_typ:ty => $ocaml_typ:ty {
+        $($t:tt)*
+    }) => {
+        unsafe impl $crate::ToOCaml<$ocaml_typ> for $rust_typ {
+            fn to_ocaml<'a>(&self, cr: &'a mut $crate::OCamlRuntime) -> $crate::OCaml<'a, $ocaml_typ> {
+                $crate::ocaml_alloc_polymorphic_variant! {
+                    cr, self => {
+                        $($t)*
+                    }
+                }
+            }
+        }
+    };
+
+    ($both_typ:ty {
+        $($t:tt)*
+    }) => {
+        $crate::impl_to_ocaml_polymorphic_variant!{
+            $both_typ => $both_typ {
+                $($t)*
+            }
+        }
+    };
+}
+
+/// Implements conversion between a Rust enum and an OCaml extensible variant.
+///
+/// See the [`impl_to_ocaml_extensible_variant!`] and [`impl_from_ocaml_extensible_variant!`]
+/// macros for more details.
+#[macro_export]
+macro_rules! impl_conv_ocaml_extensible_variant {
+    ($rust_typ:ty => $ocaml_typ:ty {
+        $($t:tt)*
+    }) => {
+        $crate::impl_to_ocaml_extensible_variant! {
+            $rust_typ => $ocaml_typ {
+                $($t)*
+            }
+        }
+
+        $crate::impl_from_ocaml_extensible_variant! {
+            $ocaml_typ => $rust_typ {
+                $($t)*
+            }
+        }
+    };
+
+    ($both_typ:ty {
+        $($t:tt)*
+    }) => {
+        $crate::impl_conv_ocaml_extensible_variant!{
+            $both_typ => $both_typ {
+                $($t)*
+            }
+        }
+    };
+}
+
+/// Unpacks an OCaml extensible variant (or exception) and maps it into a Rust enum.
+///
+/// Constructors of extensible variants don't have a tag number, and are instead identified
+/// by the extension constructor they were built with. Each constructor is mapped by the name
+/// under which its extension constructor has been registered on the OCaml side, either with
+/// `Callback.register name (Obj.Extension_constructor.of_val value)` or, for exceptions,
+/// with `Callback.register_exception name value`. Constructors are looked up the first time
+/// they are needed, and this macro panics if one of them has not been registered.
+///
+/// Constructors that are not listed produce an error result.
+///
+/// # Examples
+///
+/// ```
+/// # use ocaml_interop::*;
+/// # ocaml! { fn next_event(unit: ()) -> Event; }
+/// enum Event {
+///     Started,
+///     Progress(i64),
+///     Failed { code: i64, reason: String },
+/// }
+///
+/// // Assuming an OCaml type declaration like:
+/// //
+/// //      type event = ..
+/// //      type event +=
+/// //        | Started
+/// //        | Progress of int
+/// //        | Failed of { code: int; reason: string }
+/// //
+/// //      let () =
+/// //        Callback.register "event.Started" (Obj.Extension_constructor.of_val Started);
+/// //        Callback.register "event.Progress" (Obj.Extension_constructor.of_val (Progress 0));
+/// //        Callback.register "event.Failed"
+/// //          (Obj.Extension_constructor.of_val (Failed { code = 0; reason = "" }))
+///
+/// # fn unpack_extensible_variant_example(cr: &mut OCamlRuntime) {
+/// let ocaml_event = next_event(cr, &OCaml::unit());
+/// let result = ocaml_unpack_extensible_variant! {
+///     ocaml_event => {
+///         "event.Started"  => Event::Started,
+///         // Tag field names are mandatory
+///         "event.Progress" => Event::Progress(percent: OCamlInt),
+///         "event.Failed"   => Event::Failed { code: OCamlInt, reason: String },
 ///     }
 /// };
 /// // ...
 /// # }
 /// ```
 #[macro_export]
-macro_rules! ocaml_alloc_variant {
-    ($cr:ident, $self:ident => {
-        $($($tag:ident)::+ $(($($slot_name:ident: $slot_typ:ty),+ $(,)?))? $(,)?),+
+macro_rules! ocaml_unpack_extensible_variant {
+    ($self:ident => {
+        $($name:literal => $($tag:ident)::+
+            $(($($slot_name:ident: $slot_typ:ty),+ $(,)?))?
+            $({$($field_name:ident: $field_typ:ty),+ $(,)?})?),+ $(,)?
     }) => {
-        $crate::ocaml_alloc_variant_match!{
-            $cr, $self, 0u8, 0u8,
-
-            @units {}
-            @blocks {}
+        (|| {
+            $(
+                $crate::unpack_extensible_variant_tag!(
+                    $self, $name => $($tag)::+
+                        $(($($slot_name: $slot_typ),+))?
+                        $({$($field_name: $field_typ),+})?);
+            )+
 
-            @pending $({ $($tag)::+ $(($($slot_name: $slot_typ),+))? })+
-        }
+            Err("Unexpected extension constructor found when converting from an OCaml extensible variant")
+        })()
     };
 }
 
-/// Implements [`ToOCaml`] for mapping a Rust enum into an OCaml variant.
+/// Allocates an OCaml extensible variant (or exception), mapped from a Rust enum.
 ///
-/// The match in this conversion is exhaustive, and requires that every enum case is covered.
+/// The syntax is the same as the one of [`ocaml_unpack_extensible_variant!`], and every
+/// constructor must have been registered on the OCaml side under the given name.
 ///
-/// It is important that the order of the fields remains the same as in the OCaml type declaration.
+/// The match in this conversion is exhaustive, and requires that every enum case is covered.
 ///
 /// # Examples
 ///
 /// ```
 /// # use ocaml_interop::*;
-/// enum Movement {
-///     StepLeft,
-///     StepRight,
-///     Rotate(f64),
+/// enum Event {
+///     Started,
+///     Progress(i64),
 /// }
 ///
 /// // Assuming an OCaml type declaration like:
 /// //
-/// //      type movement =
-/// //        | StepLeft
-/// //        | StepRight
-/// //        | Rotate of float
+/// //      type event = ..
+/// //      type event += Started | Progress of int
 /// //
-/// // NOTE: What is important is the order of the tags, not their names.
-///
-/// impl_to_ocaml_variant! {
-///     // Optionally, if Rust and OCaml types don't match:
-///     // RustType => OCamlType { ... }
-///     Movement {
-///         Movement::StepLeft,
-///         Movement::StepRight,
-///         // Tag field names are mandatory
-///         Movement::Rotate(rotation: OCamlFloat),
+/// //      let () =
+/// //        Callback.register "event.Started" (Obj.Extension_constructor.of_val Started);
+/// //        Callback.register "event.Progress" (Obj.Extension_constructor.of_val (Progress 0))
+///
+/// # fn alloc_extensible_variant_example(cr: &mut OCamlRuntime) {
+/// let event = Event::Progress(50);
+/// let ocaml_event: OCaml<Event> = ocaml_alloc_extensible_variant! {
+///     cr, event => {
+///         "event.Started"  => Event::Started,
+///         "event.Progress" => Event::Progress(percent: OCamlInt),
 ///     }
-/// }
+/// };
+/// // ...
+/// # }
 /// ```
 #[macro_export]
-macro_rules! impl_to_ocaml_variant {
-    ($rust_typ:ty => $ocaml_typ:ty {
-        $($t:tt)*
-    }) => {
-        unsafe impl $crate::ToOCaml<$ocaml_typ> for $rust_typ {
-            fn to_ocaml<'a>(&self, cr: &'a mut $crate::OCamlRuntime) -> $crate::OCaml<'a, $ocaml_typ> {
-                $crate::ocaml_alloc_variant! {
-                    cr, self => {
-                        $($t)*
-                    }
-                }
-            }
-        }
-    };
-
-    ($both_typ:ty {
-        $($t:tt)*
+macro_rules! ocaml_alloc_extensible_variant {
+    ($cr:ident, $self:ident => {
+        $($name:literal => $($tag:ident)::+
+            $(($($slot_name:ident: $slot_typ:ty),+ $(,)?))?
+            $({$($field_name:ident: $field_typ:ty),+ $(,)?})?),+ $(,)?
     }) => {
-        $crate::impl_to_ocaml_variant!{
-            $both_typ => $both_typ {
-                $($t)*
-            }
+        match $self {
+            $(
+                $($tag)::+ $(($($slot_name),+))? $({$($field_name),+})? =>
+                    $crate::alloc_extensible_variant_tag!(
+                        $cr, $name
+                        $(, $($slot_name: $slot_typ),+)?
+                        $(, $($field_name: $field_typ),+)?),
+            )+
         }
     };
 }
 
-/// Implements [`FromOCaml`] for mapping an OCaml variant into a Rust enum.
+/// Implements [`FromOCaml`] for mapping an OCaml extensible variant (or exception) into a Rust enum.
 ///
-/// It is important that the order of the fields remains the same as in the OCaml type declaration.
+/// See [`ocaml_unpack_extensible_variant!`] for how constructors are identified.
 ///
 /// # Examples
 ///
 /// ```
 /// # use ocaml_interop::*;
-/// enum Movement {
-///     StepLeft,
-///     StepRight,
-///     Rotate(f64),
+/// enum PluginError {
+///     NotFound(String),
+///     Crashed { code: i64 },
 /// }
 ///
-/// // Assuming an OCaml type declaration like:
+/// // Assuming OCaml declarations like:
 /// //
-/// //      type movement = [
-/// //        | `StepLeft
-/// //        | `StepRight
-/// //        | `Rotate of float
-/// //      ]
+/// //      exception Plugin_not_found of string
+/// //      exception Plugin_crashed of { code: int }
+/// //
+/// //      let () =
+/// //        Callback.register_exception "Plugin_not_found" (Plugin_not_found "");
+/// //        Callback.register_exception "Plugin_crashed" (Plugin_crashed { code = 0 })
 ///
-/// impl_from_ocaml_polymorphic_variant! {
+/// impl_from_ocaml_extensible_variant! {
 ///     // Optionally, if Rust and OCaml types don't match:
 ///     // OCamlType => RustType { ... }
-///     Movement {
-///         StepLeft  => Movement::StepLeft,
-///         StepRight => Movement::StepRight,
-///         // Tag field names are mandatory
-///         Rotate(rotation: OCamlFloat)
-///                   => Movement::Rotate(rotation),
+///     PluginError {
+///         "Plugin_not_found" => PluginError::NotFound(name: String),
+///         "Plugin_crashed"   => PluginError::Crashed { code: OCamlInt },
 ///     }
 /// }
 /// ```
 #[macro_export]
-macro_rules! impl_from_ocaml_polymorphic_variant {
+macro_rules! impl_from_ocaml_extensible_variant {
     ($ocaml_typ:ty => $rust_typ:ty {
         $($t:tt)*
     }) => {
         unsafe impl $crate::FromOCaml<$ocaml_typ> for $rust_typ {
             fn from_ocaml(v: $crate::OCaml<$ocaml_typ>) -> Self {
-                let result = $crate::ocaml_unpack_polymorphic_variant! {
+                let result = $crate::ocaml_unpack_extensible_variant! {
                     v => {
                         $($t)*
                     }
                 };
 
                 let msg = concat!(
-                    "Failure when unpacking an OCaml<", stringify!($ocaml_typ), "> polymorphic variant into ",
-                    stringify!($rust_typ), " (unexpected tag value)");
+                    "Failure when unpacking an OCaml<", stringify!($ocaml_typ), "> extensible variant into ",
+                    stringify!($rust_typ), " (unexpected extension constructor)");
 
                 result.expect(msg)
             }
@@ -1006,7 +2160,7 @@ macro_rules! impl_from_ocaml_polymorphic_variant {
     ($both_typ:ty {
         $($t:tt)*
     }) => {
-        $crate::impl_from_ocaml_polymorphic_variant!{
+        $crate::impl_from_ocaml_extensible_variant!{
             $both_typ => $both_typ {
                 $($t)*
             }
@@ -1014,58 +2168,69 @@ macro_rules! impl_from_ocaml_polymorphic_variant {
     };
 }
 
-/// Unpacks an OCaml polymorphic variant and maps it into a Rust enum.
-///
-/// # Note
+/// Implements [`ToOCaml`] for mapping a Rust enum into an OCaml extensible variant (or exception).
 ///
-/// Unlike with [`ocaml_unpack_record!`], the result of [`ocaml_unpack_polymorphic_variant!`] is a `Result` value.
-/// An error will be returned in the case of an unexpected tag value. This may change in the future.
+/// See [`ocaml_unpack_extensible_variant!`] for how constructors are identified.
 ///
 /// # Examples
 ///
 /// ```
 /// # use ocaml_interop::*;
-/// # ocaml! { fn make_ocaml_polymorphic_movement(unit: ()) -> Movement; }
-/// enum Movement {
-///     StepLeft,
-///     StepRight,
-///     Rotate(f64),
+/// enum Event {
+///     Started,
+///     Progress(i64),
+///     Failed { code: i64, reason: String },
 /// }
 ///
 /// // Assuming an OCaml type declaration like:
 /// //
-/// //      type movement = [
-/// //        | `StepLeft
-/// //        | `StepRight
-/// //        | `Rotate of float
-/// //      ]
+/// //      type event = ..
+/// //      type event +=
+/// //        | Started
+/// //        | Progress of int
+/// //        | Failed of { code: int; reason: string }
+/// //
+/// //      let () =
+/// //        Callback.register "event.Started" (Obj.Extension_constructor.of_val Started);
+/// //        Callback.register "event.Progress" (Obj.Extension_constructor.of_val (Progress 0));
+/// //        Callback.register "event.Failed"
+/// //          (Obj.Extension_constructor.of_val (Failed { code = 0; reason = "" }))
 ///
-/// # fn unpack_polymorphic_variant_example(cr: &mut OCamlRuntime) {
-/// let ocaml_polymorphic_variant = make_ocaml_polymorphic_movement(cr, &OCaml::unit());
-/// let result = ocaml_unpack_polymorphic_variant! {
-///     ocaml_polymorphic_variant => {
-///         StepLeft  => Movement::StepLeft,
-///         StepRight => Movement::StepRight,
+/// impl_to_ocaml_extensible_variant! {
+///     // Optionally, if Rust and OCaml types don't match:
+///     // RustType => OCamlType { ... }
+///     Event {
+///         "event.Started"  => Event::Started,
 ///         // Tag field names are mandatory
-///         Rotate(rotation: OCamlFloat)
-///                   => Movement::Rotate(rotation),
+///         "event.Progress" => Event::Progress(percent: OCamlInt),
+///         "event.Failed"   => Event::Failed { code: OCamlInt, reason: String },
 ///     }
-/// }.unwrap();
-/// // ...
-/// # }
+/// }
+/// ```
 #[macro_export]
-macro_rules! ocaml_unpack_polymorphic_variant {
-    ($self:ident => {
-        $($tag:ident $(($($slot_name:ident: $slot_typ:ty),+ $(,)?))? => $conv:expr),+ $(,)?
+macro_rules! impl_to_ocaml_extensible_variant {
+    ($rust_typ:ty => $ocaml_typ:ty {
+        $($t:tt)*
     }) => {
-        (|| {
-            $(
-                $crate::unpack_polymorphic_variant_tag!(
-                    $self, $tag $(($($slot_name: $slot_typ),+))? => $conv);
-            )+
+        unsafe impl $crate::ToOCaml<$ocaml_typ> for $rust_typ {
+            fn to_ocaml<'a>(&self, cr: &'a mut $crate::OCamlRuntime) -> $crate::OCaml<'a, $ocaml_typ> {
+                $crate::ocaml_alloc_extensible_variant! {
+                    cr, self => {
+                        $($t)*
+                    }
+                }
+            }
+        }
+    };
 
-            Err("Invalid tag value found when converting from an OCaml polymorphic variant")
-        })()
+    ($both_typ:ty {
+        $($t:tt)*
+    }) => {
+        $crate::impl_to_ocaml_extensible_variant!{
+            $both_typ => $both_typ {
+                $($t)*
+            }
+        }
     };
 }
 
@@ -1210,9 +2375,19 @@ macro_rules! ocaml_alloc_variant_match {
         @blocks {
             $({ $($block_tag:ident)::+ ($($block_slot_name:ident: $block_slot_typ:ty),+) @ $block_tag_counter:expr })*
         }
+        @records {
+            $({ $($record_tag:ident)::+ {$($record_field_name:ident: $record_field_typ:ty),+} @ $record_tag_counter:expr })*
+        }
 
         @pending
-    ) => {
+    ) => {{
+        // OCaml variants can't have more than 246 non-constant constructors,
+        // higher tag values are reserved for blocks with a special meaning.
+        const _: () = assert!(
+            $current_block_tag <= 246,
+            "OCaml variants can't have more than 246 non-constant constructors"
+        );
+
         match $self {
             $(
                 $($unit_tag)::+ =>
@@ -1222,14 +2397,19 @@ macro_rules! ocaml_alloc_variant_match {
                 $($block_tag)::+($($block_slot_name),+) =>
                     $crate::ocaml_alloc_tagged_block!($cr, $block_tag_counter, $($block_slot_name: $block_slot_typ),+),
             )*
+            $(
+                $($record_tag)::+{$($record_field_name),+} =>
+                    $crate::ocaml_alloc_tagged_block!($cr, $record_tag_counter, $($record_field_name: $record_field_typ),+),
+            )*
         }
-    };
+    }};
 
     // Found unit tag, add to accumulator and increment unit variant tag number
     ($cr:ident, $self:ident, $current_block_tag:expr, $current_long_tag:expr,
 
         @units { $($unit_tags_accum:tt)* }
         @blocks { $($block_tags_accum:tt)* }
+        @records { $($record_tags_accum:tt)* }
 
         @pending
             { $($found_tag:ident)::+ }
@@ -1243,6 +2423,7 @@ macro_rules! ocaml_alloc_variant_match {
                 { $($found_tag)::+ @ $current_long_tag }
             }
             @blocks { $($block_tags_accum)* }
+            @records { $($record_tags_accum)* }
 
             @pending $($tail)*
         }
@@ -1253,6 +2434,7 @@ macro_rules! ocaml_alloc_variant_match {
 
         @units { $($unit_tags_accum:tt)* }
         @blocks { $($block_tags_accum:tt)* }
+        @records { $($record_tags_accum:tt)* }
 
         @pending
             { $($found_tag:ident)::+ ($($found_slot_name:ident: $found_slot_typ:ty),+) }
@@ -1266,6 +2448,34 @@ macro_rules! ocaml_alloc_variant_match {
                 $($block_tags_accum)*
                 { $($found_tag)::+ ($($found_slot_name: $found_slot_typ),+) @ $current_block_tag }
             }
+            @records { $($record_tags_accum)* }
+
+            @pending $($tail)*
+        }
+    };
+
+    // Found inline record tag, add to accumulator and increment block variant tag number.
+    // Fields are stored in the order in which they are listed, which must be the order
+    // of the OCaml declaration, the order of the Rust declaration doesn't matter.
+    ($cr:ident, $self:ident, $current_block_tag:expr, $current_long_tag:expr,
+
+        @units { $($unit_tags_accum:tt)* }
+        @blocks { $($block_tags_accum:tt)* }
+        @records { $($record_tags_accum:tt)* }
+
+        @pending
+            { $($found_tag:ident)::+ {$($found_field_name:ident: $found_field_typ:ty),+} }
+            $($tail:tt)*
+    ) => {
+        $crate::ocaml_alloc_variant_match!{
+            $cr, $self, {1u8 + $current_block_tag}, $current_long_tag,
+
+            @units { $($unit_tags_accum)* }
+            @blocks { $($block_tags_accum)* }
+            @records {
+                $($record_tags_accum)*
+                { $($found_tag)::+ {$($found_field_name: $found_field_typ),+} @ $current_block_tag }
+            }
 
             @pending $($tail)*
         }
@@ -1313,19 +2523,157 @@ macro_rules! unpack_polymorphic_variant_tag {
     };
 }
 
+#[doc(hidden)]
+#[macro_export]
+macro_rules! alloc_polymorphic_variant_tag {
+    ($cr:ident, $tag:ident) => {
+        unsafe { $crate::OCaml::new($cr, $crate::polymorphic_variant_tag_hash!($tag)) }
+    };
+
+    ($cr:ident, $tag:ident($slot_name:ident: $slot_typ:ty)) => {
+        $crate::ocaml_frame!($cr, (payload_root), {
+            let payload: $crate::OCamlRef<$slot_typ> =
+                $crate::to_ocaml!($cr, $slot_name, payload_root);
+            $crate::alloc_polymorphic_variant_tag!(@block $cr, $tag, payload)
+        })
+    };
+
+    ($cr:ident, $tag:ident($($slot_name:ident: $slot_typ:ty),+)) => {
+        $crate::ocaml_frame!($cr, (payload_root), {
+            let payload: $crate::OCamlRef<($($slot_typ),+)> =
+                $crate::to_ocaml!($cr, ($($slot_name),+), payload_root);
+            $crate::alloc_polymorphic_variant_tag!(@block $cr, $tag, payload)
+        })
+    };
+
+    (@block $cr:ident, $tag:ident, $payload:ident) => {
+        unsafe {
+            let block = $crate::internal::caml_alloc(
+                2, $crate::internal::tag::TAG_POLYMORPHIC_VARIANT);
+            $crate::internal::store_field(block, 0, $crate::polymorphic_variant_tag_hash!($tag));
+            $crate::internal::store_field(block, 1, $payload.get_raw());
+            $crate::OCaml::new($cr, block)
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! unpack_extensible_variant_tag {
+    ($self:ident, $name:literal => $($tag:ident)::+) => {
+        if $self.is_block() && unsafe { $self.raw() } == $crate::extension_constructor_slot!($name) {
+            return Ok($($tag)::+);
+        }
+    };
+
+    // Parens: tuple
+    ($self:ident, $name:literal => $($tag:ident)::+ ($($slot_name:ident: $slot_typ:ty),+)) => {
+        $crate::unpack_extensible_variant_tag!(
+            @block $self, $name, $($slot_name: $slot_typ),+ => $($tag)::+($($slot_name),+))
+    };
+
+    // Braces: record
+    ($self:ident, $name:literal => $($tag:ident)::+ {$($slot_name:ident: $slot_typ:ty),+}) => {
+        $crate::unpack_extensible_variant_tag!(
+            @block $self, $name, $($slot_name: $slot_typ),+ => $($tag)::+{$($slot_name),+})
+    };
+
+    // Constructors with arguments are blocks with the extension constructor as first field
+    (@block $self:ident, $name:literal, $($slot_name:ident: $slot_typ:ty),+ => $conv:expr) => {
+        if $self.is_block()
+            && $self.tag_value() == $crate::internal::tag::TAG_EXTENSION_CONSTRUCTOR
+            && unsafe { $self.field::<()>(0).raw() } == $crate::extension_constructor_slot!($name)
+        {
+            let mut current_field = 1;
+
+            $(
+                let $slot_name = unsafe { $self.field::<$slot_typ>(current_field).to_rust() };
+                current_field += 1;
+            )+
+
+            return Ok($conv);
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! alloc_extensible_variant_tag {
+    ($cr:ident, $name:literal) => {
+        unsafe { $crate::OCaml::new($cr, $crate::extension_constructor_slot!($name)) }
+    };
+
+    ($cr:ident, $name:literal, $($field:ident : $ocaml_typ:ty),+) => {
+        unsafe {
+            $crate::ocaml_frame!($cr, (block), {
+                let mut current = 1;
+                let field_count = 1 + $crate::count_fields!($($field)*);
+                let block: $crate::OCamlRef<()> = block.keep_raw($crate::internal::caml_alloc(
+                    field_count, $crate::internal::tag::TAG_EXTENSION_CONSTRUCTOR));
+                // Read after the allocation, which may have moved the extension constructor
+                $crate::internal::store_field(
+                    block.get_raw(), 0, $crate::extension_constructor_slot!($name));
+                $(
+                    let $field: $crate::OCaml<$ocaml_typ> = $crate::to_ocaml!($cr, $field);
+                    $crate::internal::store_field(block.get_raw(), current, $field.raw());
+                    current += 1;
+                )+
+                $crate::OCaml::new($cr, block.get_raw())
+            })
+        }
+    };
+}
+
 #[doc(hidden)]
 #[macro_export]
 macro_rules! ocaml_closure_reference {
     ($var:ident, $name:ident) => {
-        static NAME: &str = stringify!($name);
-        static mut OC: Option<$crate::internal::OCamlClosure> = None;
-        static INIT: ::std::sync::Once = ::std::sync::Once::new();
-        let $var = unsafe {
-            INIT.call_once(|| {
-                OC = $crate::internal::OCamlClosure::named(NAME);
-            });
-            OC.unwrap_or_else(|| panic!("OCaml closure with name '{}' not registered", NAME))
-        };
+        static CLOSURE: $crate::internal::OCamlClosureSlot =
+            $crate::internal::OCamlClosureSlot::new(stringify!($name));
+        let $var = $crate::ocaml_closure_reference!(@resolve CLOSURE);
+    };
+
+    (@resolve $slot:path) => {
+        match $slot.resolve() {
+            Ok(closure) => closure,
+            Err(err) => panic!("{}", err),
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! ocaml_function_module {
+    ($vis:vis $name:ident) => {
+        #[doc = concat!("Registration of the `", stringify!($name), "` OCaml function.")]
+        #[allow(dead_code)]
+        $vis mod $name {
+            pub(super) static CLOSURE: $crate::internal::OCamlClosureSlot =
+                $crate::internal::OCamlClosureSlot::new(stringify!($name));
+
+            /// Returns `true` if the function has been registered from OCaml.
+            pub fn is_available() -> bool {
+                CLOSURE.resolve().is_ok()
+            }
+
+            /// Looks up the function, returning an error if it hasn't been registered from OCaml.
+            pub fn try_resolve() -> Result<(), $crate::OCamlFunctionNotRegistered> {
+                CLOSURE.resolve().map(|_| ())
+            }
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! ocaml_missing_functions {
+    ($missing:ident,) => {};
+
+    ($missing:ident, $vis:vis fn $name:ident($($args:tt)*) $(-> $rtyp:ty)?; $($t:tt)*) => {
+        if !$name::is_available() {
+            $missing.push(stringify!($name));
+        }
+        $crate::ocaml_missing_functions!($missing, $($t)*);
     };
 }
 
@@ -1541,3 +2889,21 @@ macro_rules! polymorphic_variant_tag_hash {
         }
     }};
 }
+
+// Extension constructors are looked up again until they are registered, and their current
+// location is read on every use because they are moved by the GC.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! extension_constructor_slot {
+    ($name:literal) => {{
+        static SLOT: $crate::internal::OCamlNamedSlot =
+            $crate::internal::OCamlNamedSlot::new($name);
+        match SLOT.get() {
+            Some(constructor) => unsafe { *constructor },
+            None => panic!(
+                "OCaml extension constructor with name '{}' not registered",
+                $name
+            ),
+        }
+    }};
+}
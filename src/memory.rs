@@ -2,19 +2,24 @@
 // SPDX-License-Identifier: MIT
 
 use crate::{
-    conv::FromOCaml,
-    mlvalues::{tag, Intnat, OCamlBytes, OCamlFloat, OCamlInt32, OCamlInt64, OCamlList, RawOCaml},
+    conv::{FromOCaml, FromOCamlBorrowed},
+    mlvalues::{
+        tag, tag_val, wosize_val, DynBox, Intnat, OCamlBytes, OCamlFloat, OCamlInt32, OCamlInt64,
+        OCamlList, OCamlNativeint, OCamlRefCell, RawOCaml,
+    },
     runtime::OCamlRuntime,
     value::OCaml,
 };
-use core::{cell::UnsafeCell, marker::PhantomData, ptr};
+use core::{any::Any, cell::UnsafeCell, marker::PhantomData, ptr};
 pub use ocaml_sys::{
     caml_alloc, local_roots as ocaml_sys_local_roots, set_local_roots as ocaml_sys_set_local_roots,
     store_field,
 };
 use ocaml_sys::{
-    caml_alloc_string, caml_alloc_tuple, caml_copy_double, caml_copy_int32, caml_copy_int64,
-    string_val,
+    caml_alloc_custom, caml_alloc_string, caml_alloc_tuple, caml_copy_double, caml_copy_int32,
+    caml_copy_int64, caml_copy_nativeint, caml_modify_generational_global_root,
+    caml_register_generational_global_root, caml_remove_generational_global_root,
+    custom_operations, string_val,
 };
 
 // Structure representing a block in the list of OCaml's GC local roots.
@@ -148,6 +153,17 @@ impl<T> OCamlCell<T> {
         RustT::from_ocaml(cr.get(self))
     }
 
+    /// Converts this value into a Rust value that may borrow from it.
+    ///
+    /// The result borrows the OCaml runtime, which cannot perform allocations while it is alive.
+    pub fn to_rust_borrowed<'a, RustT>(&self, cr: &'a OCamlRuntime) -> RustT
+    where
+        T: 'a,
+        RustT: FromOCamlBorrowed<'a, T>,
+    {
+        RustT::from_ocaml_borrowed(cr.get(self))
+    }
+
     /// Borrows the raw value contained in this root.
     ///
     /// # Safety
@@ -156,6 +172,178 @@ impl<T> OCamlCell<T> {
     pub unsafe fn get_raw(&self) -> RawOCaml {
         *self.cell.get()
     }
+
+    /// Replaces field `i` of the block contained in this root, using `caml_modify`.
+    ///
+    /// Records that only have float fields store them unboxed (`Double_array_tag`), in that
+    /// case `value` must be a float, which is copied into the field.
+    ///
+    /// # Safety
+    ///
+    /// This method is unsafe because there is no way to validate that the block has a
+    /// mutable field `i` of type `F`.
+    #[doc(hidden)]
+    pub unsafe fn set_field<F>(&self, _cr: &mut OCamlRuntime, i: usize, value: OCamlRef<F>) {
+        let block = self.get_raw();
+        if tag_val(block) == tag::DOUBLE_ARRAY {
+            let len =
+                wosize_val(block) * core::mem::size_of::<RawOCaml>() / core::mem::size_of::<f64>();
+            assert!(
+                i < len,
+                "trying to access a field bigger than the OCaml block value"
+            );
+            let d = *(value.get_raw() as *const f64);
+            ptr::write((block as *mut f64).add(i), d);
+        } else {
+            assert!(
+                i < wosize_val(block),
+                "trying to access a field bigger than the OCaml block value"
+            );
+            store_field(block, i, value.get_raw());
+        }
+    }
+}
+
+impl<A> OCamlCell<OCamlRefCell<A>> {
+    /// Replaces the value contained in this OCaml reference (`r := value`).
+    pub fn set(&self, cr: &mut OCamlRuntime, value: OCamlRef<A>) {
+        unsafe { self.set_field(cr, 0, value) }
+    }
+}
+
+impl OCamlCell<OCamlBytes> {
+    /// Returns a mutable `[u8]` reference to the internal bytes of this value.
+    ///
+    /// The OCaml runtime stays borrowed while the reference is alive, so no allocations (that
+    /// could move the value) can happen in the meantime.
+    pub fn as_mut_bytes<'a>(&self, cr: &'a mut OCamlRuntime) -> &'a mut [u8] {
+        let s = cr.get(self);
+        let len = s.as_bytes().len();
+        unsafe { core::slice::from_raw_parts_mut(string_val(s.raw()), len) }
+    }
+}
+
+/// A root that keeps an OCaml value alive independently of any [`ocaml_frame!`].
+///
+/// Unlike roots reserved with [`ocaml_frame!`], global roots are not bound to a scope,
+/// and remain valid until dropped. They are more expensive to create and release, and should
+/// be used only for values that have to outlive the frame in which they were obtained.
+///
+/// Global roots can only be accessed and dropped from the thread that is running the OCaml runtime.
+pub struct OCamlGlobalRoot<T> {
+    cell: Box<OCamlCell<T>>,
+    // Global roots are not thread-safe, they must stay in the thread of the OCaml runtime.
+    _not_send: PhantomData<*const ()>,
+}
+
+impl<T> OCamlGlobalRoot<T> {
+    /// Creates a new global root that keeps `val` alive.
+    pub fn new(val: OCaml<T>) -> Self {
+        let cell = Box::new(OCamlCell {
+            cell: UnsafeCell::new(unsafe { val.raw() }),
+            _marker: PhantomData,
+        });
+        unsafe { caml_register_generational_global_root(cell.cell.get()) };
+        OCamlGlobalRoot {
+            cell,
+            _not_send: PhantomData,
+        }
+    }
+
+    /// Returns an [`OCamlRef`] that points to the value kept by this root.
+    pub fn as_ref(&self) -> OCamlRef<'_, T> {
+        &self.cell
+    }
+
+    /// Returns the OCaml value kept by this root.
+    pub fn get<'a>(&self, cr: &'a OCamlRuntime) -> OCaml<'a, T> {
+        cr.get(&self.cell)
+    }
+
+    /// Replaces the value kept by this root.
+    pub fn set(&self, val: OCaml<T>) {
+        unsafe { caml_modify_generational_global_root(self.cell.cell.get(), val.raw()) };
+    }
+}
+
+impl<T> Drop for OCamlGlobalRoot<T> {
+    fn drop(&mut self) {
+        unsafe { caml_remove_generational_global_root(self.cell.cell.get()) };
+    }
+}
+
+// Custom blocks that own Rust values store a `Box<dyn Any>` in their data
+// section, which is dropped by the finalizer.
+
+struct StaticCustomOperations(custom_operations);
+
+unsafe impl Sync for StaticCustomOperations {}
+
+extern "C" fn drop_box_value(v: RawOCaml) {
+    unsafe {
+        let data = ocaml_sys::field(v, 1) as *mut Box<dyn Any>;
+        ptr::drop_in_place(data);
+    }
+}
+
+static BOX_OPS: StaticCustomOperations = StaticCustomOperations(custom_operations {
+    identifier: "_ocaml_interop_box\0".as_ptr() as *const ocaml_sys::Char,
+    finalize: Some(drop_box_value),
+    compare: None,
+    hash: None,
+    serialize: None,
+    deserialize: None,
+    compare_ext: None,
+    fixed_length: ptr::null(),
+});
+
+extern "C" {
+    // Declared here with a `*const` argument like `caml_alloc_custom`, the runtime doesn't
+    // modify the custom operations.
+    fn caml_alloc_custom_mem(
+        ops: *const custom_operations,
+        size: ocaml_sys::Uintnat,
+        mem: ocaml_sys::Size,
+    ) -> RawOCaml;
+}
+
+pub fn alloc_box<'a, A: 'static>(cr: &'a mut OCamlRuntime, data: A) -> OCaml<'a, DynBox<A>> {
+    unsafe {
+        let value = caml_alloc_custom(&BOX_OPS.0, core::mem::size_of::<Box<dyn Any>>(), 0, 1);
+        init_box(cr, value, data)
+    }
+}
+
+/// Like [`alloc_box`], but `size_hint` bytes of memory owned by `data` outside of the
+/// OCaml heap are accounted by the GC, which will collect more often accordingly.
+pub fn alloc_box_with_size<'a, A: 'static>(
+    cr: &'a mut OCamlRuntime,
+    data: A,
+    size_hint: usize,
+) -> OCaml<'a, DynBox<A>> {
+    unsafe {
+        let value =
+            caml_alloc_custom_mem(&BOX_OPS.0, core::mem::size_of::<Box<dyn Any>>(), size_hint);
+        init_box(cr, value, data)
+    }
+}
+
+unsafe fn init_box<A: 'static>(
+    cr: &OCamlRuntime,
+    value: RawOCaml,
+    data: A,
+) -> OCaml<'_, DynBox<A>> {
+    let boxed: Box<dyn Any> = Box::new(data);
+    let field = ocaml_sys::field(value, 1) as *mut Box<dyn Any>;
+    ptr::write(field, boxed);
+    OCaml::new(cr, value)
+}
+
+pub unsafe fn box_value_ref<'a, A: 'static>(value: RawOCaml) -> &'a A {
+    let data = ocaml_sys::field(value, 1) as *const Box<dyn Any>;
+    (*data)
+        .downcast_ref::<A>()
+        .expect("unexpected type of boxed Rust value")
 }
 
 pub fn alloc_bytes<'a>(cr: &'a mut OCamlRuntime, s: &[u8]) -> OCaml<'a, OCamlBytes> {
@@ -186,6 +374,10 @@ pub fn alloc_int64(cr: &mut OCamlRuntime, i: i64) -> OCaml<OCamlInt64> {
     unsafe { OCaml::new(cr, caml_copy_int64(i)) }
 }
 
+pub fn alloc_nativeint(cr: &mut OCamlRuntime, i: isize) -> OCaml<'_, OCamlNativeint> {
+    unsafe { OCaml::new(cr, caml_copy_nativeint(i)) }
+}
+
 pub fn alloc_double(cr: &mut OCamlRuntime, d: f64) -> OCaml<OCamlFloat> {
     unsafe { OCaml::new(cr, caml_copy_double(d)) }
 }
@@ -194,59 +386,92 @@ pub fn alloc_double(cr: &mut OCamlRuntime, d: f64) -> OCaml<OCamlFloat> {
 // small values (like tuples and conses are) without going through `caml_modify` to get
 // a little bit of extra performance.
 
-pub fn alloc_some<'a, A>(cr: &'a mut OCamlRuntime, value: OCamlRef<A>) -> OCaml<'a, Option<A>> {
-    unsafe {
-        let ocaml_some = caml_alloc(1, tag::SOME);
-        store_field(ocaml_some, 0, value.get_raw());
-        OCaml::new(cr, ocaml_some)
-    }
-}
-
-pub fn alloc_tuple<'a, F, S>(
+pub fn alloc_ref<'a, A>(
     cr: &'a mut OCamlRuntime,
-    fst: OCamlRef<F>,
-    snd: OCamlRef<S>,
-) -> OCaml<'a, (F, S)> {
+    value: OCamlRef<A>,
+) -> OCaml<'a, OCamlRefCell<A>> {
     unsafe {
-        let ocaml_tuple = caml_alloc_tuple(2);
-        store_field(ocaml_tuple, 0, fst.get_raw());
-        store_field(ocaml_tuple, 1, snd.get_raw());
-        OCaml::new(cr, ocaml_tuple)
+        let ocaml_ref = caml_alloc(1, 0);
+        store_field(ocaml_ref, 0, value.get_raw());
+        OCaml::new(cr, ocaml_ref)
     }
 }
 
-pub fn alloc_tuple_3<'a, F, S, T3>(
-    cr: &'a mut OCamlRuntime,
-    fst: OCamlRef<F>,
-    snd: OCamlRef<S>,
-    elt3: OCamlRef<T3>,
-) -> OCaml<'a, (F, S, T3)> {
+pub fn alloc_some<'a, A>(cr: &'a mut OCamlRuntime, value: OCamlRef<A>) -> OCaml<'a, Option<A>> {
     unsafe {
-        let ocaml_tuple = caml_alloc_tuple(3);
-        store_field(ocaml_tuple, 0, fst.get_raw());
-        store_field(ocaml_tuple, 1, snd.get_raw());
-        store_field(ocaml_tuple, 2, elt3.get_raw());
-        OCaml::new(cr, ocaml_tuple)
+        let ocaml_some = caml_alloc(1, tag::SOME);
+        store_field(ocaml_some, 0, value.get_raw());
+        OCaml::new(cr, ocaml_some)
     }
 }
 
-pub fn alloc_tuple_4<'a, F, S, T3, T4>(
-    cr: &'a mut OCamlRuntime,
-    fst: OCamlRef<F>,
-    snd: OCamlRef<S>,
-    elt3: OCamlRef<T3>,
-    elt4: OCamlRef<T4>,
-) -> OCaml<'a, (F, S, T3, T4)> {
-    unsafe {
-        let ocaml_tuple = caml_alloc_tuple(4);
-        store_field(ocaml_tuple, 0, fst.get_raw());
-        store_field(ocaml_tuple, 1, snd.get_raw());
-        store_field(ocaml_tuple, 2, elt3.get_raw());
-        store_field(ocaml_tuple, 3, elt4.get_raw());
-        OCaml::new(cr, ocaml_tuple)
-    }
+macro_rules! impl_alloc_tuple {
+    ($name:ident, $len:expr, $($elt:ident: $typ:ident => $index:expr),+ $(,)?) => {
+        #[allow(clippy::too_many_arguments)]
+        pub fn $name<'a, $($typ),+>(
+            cr: &'a mut OCamlRuntime,
+            $($elt: OCamlRef<$typ>),+
+        ) -> OCaml<'a, ($($typ),+)> {
+            unsafe {
+                let ocaml_tuple = caml_alloc_tuple($len);
+                $(store_field(ocaml_tuple, $index, $elt.get_raw());)+
+                OCaml::new(cr, ocaml_tuple)
+            }
+        }
+    };
 }
 
+impl_alloc_tuple!(alloc_tuple, 2, fst: F => 0, snd: S => 1);
+impl_alloc_tuple!(alloc_tuple_3, 3, fst: F => 0, snd: S => 1, elt3: T3 => 2);
+impl_alloc_tuple!(alloc_tuple_4, 4, fst: F => 0, snd: S => 1, elt3: T3 => 2, elt4: T4 => 3);
+impl_alloc_tuple!(
+    alloc_tuple_5,
+    5,
+    fst: F => 0, snd: S => 1, elt3: T3 => 2, elt4: T4 => 3, elt5: T5 => 4,
+);
+impl_alloc_tuple!(
+    alloc_tuple_6,
+    6,
+    fst: F => 0, snd: S => 1, elt3: T3 => 2, elt4: T4 => 3, elt5: T5 => 4, elt6: T6 => 5,
+);
+impl_alloc_tuple!(
+    alloc_tuple_7,
+    7,
+    fst: F => 0, snd: S => 1, elt3: T3 => 2, elt4: T4 => 3, elt5: T5 => 4, elt6: T6 => 5,
+    elt7: T7 => 6,
+);
+impl_alloc_tuple!(
+    alloc_tuple_8,
+    8,
+    fst: F => 0, snd: S => 1, elt3: T3 => 2, elt4: T4 => 3, elt5: T5 => 4, elt6: T6 => 5,
+    elt7: T7 => 6, elt8: T8 => 7,
+);
+impl_alloc_tuple!(
+    alloc_tuple_9,
+    9,
+    fst: F => 0, snd: S => 1, elt3: T3 => 2, elt4: T4 => 3, elt5: T5 => 4, elt6: T6 => 5,
+    elt7: T7 => 6, elt8: T8 => 7, elt9: T9 => 8,
+);
+impl_alloc_tuple!(
+    alloc_tuple_10,
+    10,
+    fst: F => 0, snd: S => 1, elt3: T3 => 2, elt4: T4 => 3, elt5: T5 => 4, elt6: T6 => 5,
+    elt7: T7 => 6, elt8: T8 => 7, elt9: T9 => 8, elt10: T10 => 9,
+);
+impl_alloc_tuple!(
+    alloc_tuple_11,
+    11,
+    fst: F => 0, snd: S => 1, elt3: T3 => 2, elt4: T4 => 3, elt5: T5 => 4, elt6: T6 => 5,
+    elt7: T7 => 6, elt8: T8 => 7, elt9: T9 => 8, elt10: T10 => 9, elt11: T11 => 10,
+);
+impl_alloc_tuple!(
+    alloc_tuple_12,
+    12,
+    fst: F => 0, snd: S => 1, elt3: T3 => 2, elt4: T4 => 3, elt5: T5 => 4, elt6: T6 => 5,
+    elt7: T7 => 6, elt8: T8 => 7, elt9: T9 => 8, elt10: T10 => 9, elt11: T11 => 10,
+    elt12: T12 => 11,
+);
+
 pub fn alloc_cons<'a, A>(
     cr: &'a mut OCamlRuntime,
     head: OCamlRef<A>,
@@ -27,11 +27,73 @@ pub struct OCamlBytes {}
 /// `OCaml<OCamlInt>` is an OCaml integer (tagged and unboxed) value.
 pub type OCamlInt = Intnat;
 
+/// `OCaml<OCamlRefCell<T>>` is a reference to an OCaml `'a ref` value containing a value of type `T`.
+pub struct OCamlRefCell<A> {
+    _marker: PhantomData<A>,
+}
+
 /// `OCaml<OCamlInt32>` is a reference to an OCaml `Int32.t` (boxed `int32`) value.
 pub struct OCamlInt32 {}
 
 /// `OCaml<OCamlInt64>` is a reference to an OCaml `Int64.t` (boxed `int64`) value.
 pub struct OCamlInt64 {}
 
+/// `OCaml<OCamlNativeint>` is a reference to an OCaml `Nativeint.t` (boxed `nativeint`) value.
+pub struct OCamlNativeint {}
+
+/// `OCaml<OCamlChar>` is an OCaml `char` (an immediate integer in the `0..=255` range) value.
+pub struct OCamlChar {}
+
+/// `OCaml<OCamlUchar>` is an OCaml `Uchar.t` (an immediate Unicode scalar value) value.
+pub struct OCamlUchar {}
+
 /// `OCaml<OCamlFloat>` is a reference to an OCaml `float` (boxed `float`) value.
 pub struct OCamlFloat {}
+
+/// `OCaml<DynBox<T>>` is a reference to an OCaml custom block that owns a boxed Rust value of type `T`.
+///
+/// The Rust value is dropped when the OCaml value is collected by the GC.
+pub struct DynBox<A> {
+    _marker: PhantomData<A>,
+}
+
+/// `OCaml<OCamlFunction<A, Ret>>` is a reference to an OCaml function value of type `a -> ret`.
+///
+/// Functions of more than one argument are curried: `a -> b -> ret` is `OCamlFunction<A, OCamlFunction<B, Ret>>`.
+pub struct OCamlFunction<A, Ret> {
+    _marker: PhantomData<(A, Ret)>,
+}
+
+/// `OCaml<OCamlHashtbl<K, V>>` is a reference to an OCaml `Hashtbl.t` with keys of type `K`
+/// and values of type `V`.
+///
+/// # Note
+///
+/// Converting Rust maps into hash tables requires the `ocaml_interop` OCaml library (in the
+/// `ocaml/` directory of this repository) to be linked into the program.
+pub struct OCamlHashtbl<K, V> {
+    _marker: PhantomData<(K, V)>,
+}
+
+/// `OCaml<OCamlMap<K, V>>` is a reference to the `t` type of an OCaml map produced by `Map.Make`,
+/// with keys of type `K` and values of type `V`.
+pub struct OCamlMap<K, V> {
+    _marker: PhantomData<(K, V)>,
+}
+
+/// `OCaml<OCamlSet<T>>` is a reference to the `t` type of an OCaml set produced by `Set.Make`,
+/// with elements of type `T`.
+pub struct OCamlSet<A> {
+    _marker: PhantomData<A>,
+}
+
+/// `OCaml<OCamlWeak<T>>` is a reference to an OCaml `Weak.t` array of values of type `T`.
+pub struct OCamlWeak<A> {
+    _marker: PhantomData<A>,
+}
+
+/// `OCaml<OCamlEphemeron<K, D>>` is a reference to an OCaml ephemeron with keys of type `K`
+/// and data of type `D` (an `Ephemeron.K1.t` when it has a single key, or an `Ephemeron.Kn.t`).
+pub struct OCamlEphemeron<K, D> {
+    _marker: PhantomData<(K, D)>,
+}
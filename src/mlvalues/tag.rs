@@ -1,8 +1,11 @@
 // Copyright (c) SimpleStaking and Tezedge Contributors
 // SPDX-License-Identifier: MIT
 
-pub use ocaml_sys::{Tag, CLOSURE, NO_SCAN, STRING, TAG_CONS as CONS, TAG_SOME as SOME};
+pub use ocaml_sys::{
+    Tag, CLOSURE, DOUBLE_ARRAY, NO_SCAN, OBJECT, STRING, TAG_CONS as CONS, TAG_SOME as SOME,
+};
 
 pub const TAG_POLYMORPHIC_VARIANT: Tag = 0;
+pub const TAG_EXTENSION_CONSTRUCTOR: Tag = 0;
 pub const TAG_OK: Tag = 0;
 pub const TAG_ERROR: Tag = 1;
@@ -0,0 +1,89 @@
+// Copyright (c) SimpleStaking and Tezedge Contributors
+// SPDX-License-Identifier: MIT
+
+use crate::{internal::named_value, memory::OCamlCell, mlvalues::RawOCaml, OCamlRef, OCamlRuntime};
+use core::marker::PhantomData;
+use core::sync::atomic::{AtomicPtr, Ordering};
+use std::sync::Once;
+
+/// A value of type `T` registered from OCaml with `Callback.register`.
+///
+/// Unlike functions declared with [`ocaml!`](crate::ocaml), any kind of value can be looked up:
+/// constants, records, hash tables, etc. The type `T` is not checked against the registered value.
+///
+/// The location of the value is looked up the first time it is requested and then cached,
+/// so `OCamlNamedValue` is meant to be declared as a `static`:
+///
+/// ```
+/// # use ocaml_interop::*;
+/// static PRIMES: OCamlNamedValue<OCamlList<OCamlInt>> = OCamlNamedValue::new("primes");
+///
+/// fn primes(cr: &OCamlRuntime) -> Vec<i64> {
+///     match PRIMES.get(cr) {
+///         Some(primes) => primes.to_rust(cr),
+///         None => vec![],
+///     }
+/// }
+/// ```
+///
+/// Registering a value again under the same name updates it in place, and the new value
+/// is seen through the cached location. If the value was not registered yet when it was first
+/// requested, [`OCamlNamedValue::refresh`] looks it up again.
+pub struct OCamlNamedValue<T> {
+    name: &'static str,
+    init: Once,
+    slot: AtomicPtr<RawOCaml>,
+    _marker: PhantomData<fn() -> T>,
+}
+
+// The cached location can be shared, the value can only be accessed with an `OCamlRuntime`.
+unsafe impl<T> Sync for OCamlNamedValue<T> {}
+
+impl<T> OCamlNamedValue<T> {
+    /// Creates a handle for the value registered under `name`.
+    pub const fn new(name: &'static str) -> Self {
+        OCamlNamedValue {
+            name,
+            init: Once::new(),
+            slot: AtomicPtr::new(core::ptr::null_mut()),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns the name under which the value is registered.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Returns a reference to the registered value, or `None` if no value was registered
+    /// under this name when it was first looked up.
+    ///
+    /// The reference is not tied to `cr`, so it can be passed to OCaml functions: named values
+    /// are never unregistered and their location doesn't change when the GC moves them.
+    pub fn get(&self, cr: &OCamlRuntime) -> Option<OCamlRef<'static, T>> {
+        self.init.call_once(|| self.lookup());
+        self.as_ref(cr)
+    }
+
+    /// Looks up the value again, and returns a reference to it if it is now registered.
+    pub fn refresh(&self, cr: &OCamlRuntime) -> Option<OCamlRef<'static, T>> {
+        self.init.call_once(|| {});
+        self.lookup();
+        self.as_ref(cr)
+    }
+
+    fn lookup(&self) {
+        let slot = named_value(self.name) as *mut RawOCaml;
+        self.slot.store(slot, Ordering::Release);
+    }
+
+    fn as_ref(&self, _cr: &OCamlRuntime) -> Option<OCamlRef<'static, T>> {
+        let slot = self.slot.load(Ordering::Acquire);
+        if slot.is_null() {
+            None
+        } else {
+            // Named values are global roots that are never removed
+            Some(unsafe { OCamlCell::create_ref(slot) })
+        }
+    }
+}
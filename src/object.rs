@@ -0,0 +1,131 @@
+// Copyright (c) SimpleStaking and Tezedge Contributors
+// SPDX-License-Identifier: MIT
+
+use crate::{
+    closure::OCamlClosure,
+    memory::OCamlCell,
+    mlvalues::{field_val, RawOCaml},
+    OCaml, OCamlRef, OCamlRuntime,
+};
+use ocaml_sys::{caml_get_public_method, caml_hash_variant, int_val};
+use std::ffi::CString;
+
+/// `OCaml<OCamlObject>` is a reference to an OCaml object.
+///
+/// Objects are structurally typed, so the types of the arguments and results of their methods
+/// are provided when calling them.
+pub struct OCamlObject {}
+
+// Objects are blocks with the table of methods as first field and the object id as second field.
+// Public methods are looked up by the hash of their label, like polymorphic variant tags.
+fn public_method(obj: RawOCaml, name: &str) -> Option<RawOCaml> {
+    let name = CString::new(name).ok()?;
+    let method = unsafe {
+        let label = caml_hash_variant(name.as_ptr() as *const u8);
+        caml_get_public_method(obj, label)
+    };
+    if method == 0 {
+        None
+    } else {
+        Some(method)
+    }
+}
+
+impl<'a> OCaml<'a, OCamlObject> {
+    /// Returns the unique identifier of this object, like `Oo.id` does.
+    pub fn id(&self) -> i64 {
+        unsafe { int_val(*field_val(self.raw, 1)) as i64 }
+    }
+
+    /// Returns `true` if this object has a public method named `name`.
+    pub fn has_method(&self, name: &str) -> bool {
+        public_method(self.raw, name).is_some()
+    }
+}
+
+impl OCamlCell<OCamlObject> {
+    fn method(&self, name: &str) -> RawOCaml {
+        public_method(unsafe { self.get_raw() }, name)
+            .unwrap_or_else(|| panic!("OCaml object has no public method named '{}'", name))
+    }
+
+    /// Calls the public method `name` of this object, that takes no arguments.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the object has no such method, or if the call raises an OCaml exception.
+    pub fn call_method<'a, R>(&self, cr: &'a mut OCamlRuntime, name: &str) -> OCaml<'a, R> {
+        let method = self.method(name);
+        unsafe { OCamlClosure::of_ptr(&method) }.call(cr, self)
+    }
+
+    /// Calls the public method `name` of this object with one argument.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the object has no such method, or if the call raises an OCaml exception.
+    pub fn call_method1<'a, A, R>(
+        &self,
+        cr: &'a mut OCamlRuntime,
+        name: &str,
+        arg: OCamlRef<A>,
+    ) -> OCaml<'a, R> {
+        let method = self.method(name);
+        unsafe { OCamlClosure::of_ptr(&method) }.call2(cr, self, arg)
+    }
+
+    /// Calls the public method `name` of this object with two arguments.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the object has no such method, or if the call raises an OCaml exception.
+    pub fn call_method2<'a, A, B, R>(
+        &self,
+        cr: &'a mut OCamlRuntime,
+        name: &str,
+        arg1: OCamlRef<A>,
+        arg2: OCamlRef<B>,
+    ) -> OCaml<'a, R> {
+        let method = self.method(name);
+        unsafe { OCamlClosure::of_ptr(&method) }.call3(cr, self, arg1, arg2)
+    }
+
+    /// Calls the public method `name` of this object with three arguments.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the object has no such method, or if the call raises an OCaml exception.
+    pub fn call_method3<'a, A, B, C, R>(
+        &self,
+        cr: &'a mut OCamlRuntime,
+        name: &str,
+        arg1: OCamlRef<A>,
+        arg2: OCamlRef<B>,
+        arg3: OCamlRef<C>,
+    ) -> OCaml<'a, R> {
+        let mut args = unsafe { [arg1.get_raw(), arg2.get_raw(), arg3.get_raw()] };
+        self.call_method_n(cr, name, &mut args)
+    }
+
+    /// Calls the public method `name` of this object with any number of arguments.
+    ///
+    /// Unlike `OCamlClosure::call_n`, `args` only contains the arguments of the method: the
+    /// object itself is passed to the method as its first argument, which is why `call_method1`
+    /// calls the method like `OCamlClosure::call2` would.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the object has no such method, or if the call raises an OCaml exception.
+    pub fn call_method_n<'a, R>(
+        &self,
+        cr: &'a mut OCamlRuntime,
+        name: &str,
+        args: &mut [RawOCaml],
+    ) -> OCaml<'a, R> {
+        let method = self.method(name);
+        let mut all_args = Vec::with_capacity(args.len() + 1);
+        all_args.push(unsafe { self.get_raw() });
+        all_args.extend_from_slice(args);
+        unsafe { OCamlClosure::of_ptr(&method) }.call_n(cr, &mut all_args)
+    }
+}
@@ -1,10 +1,32 @@
 // Copyright (c) SimpleStaking and Tezedge Contributors
 // SPDX-License-Identifier: MIT
 
+use core::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
 use ocaml_sys::{caml_shutdown, caml_startup};
-use std::marker::PhantomData;
+use std::{
+    ffi::CString,
+    marker::PhantomData,
+    sync::Arc,
+    task::{Wake, Waker},
+    thread::{self, Thread},
+};
 
-use crate::{memory::GCFrame, memory::OCamlRef, value::OCaml};
+use crate::{
+    error::{OCamlException, OCamlRuntimeInitError},
+    memory::GCFrame,
+    memory::OCamlRef,
+    mlvalues::{extract_exception, is_exception_result, RawOCaml, FALSE, TRUE},
+    value::OCaml,
+};
+
+extern "C" {
+    fn caml_startup_exn(argv: *const *const i8) -> RawOCaml;
+    fn caml_record_backtrace(vflag: RawOCaml) -> RawOCaml;
+}
 
 /// OCaml runtime handle.
 pub struct OCamlRuntime {
@@ -18,6 +40,11 @@ impl OCamlRuntime {
         OCamlRuntime { _private: () }
     }
 
+    /// Returns a builder that can be used to configure the initialization of the OCaml runtime.
+    pub fn builder() -> OCamlRuntimeBuilder {
+        OCamlRuntimeBuilder::new()
+    }
+
     /// Initializes the OCaml runtime.
     pub fn init_persistent() {
         let arg0 = "ocaml\0".as_ptr() as *const i8;
@@ -47,6 +74,17 @@ impl OCamlRuntime {
         OCamlBlockingSection::new().perform(f)
     }
 
+    /// Release the OCaml runtime lock, wait for `future` to complete, and re-acquire the OCaml runtime lock.
+    ///
+    /// The current thread is blocked until the future completes, but other threads
+    /// can make use of the OCaml runtime in the meantime.
+    pub fn releasing_runtime_await<F>(&mut self, future: F) -> F::Output
+    where
+        F: Future,
+    {
+        self.releasing_runtime(|| block_on(future))
+    }
+
     #[doc(hidden)]
     pub fn open_frame<'a, 'gc>(&'a self) -> GCFrame<'gc> {
         Default::default()
@@ -63,10 +101,241 @@ impl OCamlRuntime {
 
 impl Drop for OCamlRuntime {
     fn drop(&mut self) {
+        crate::lwt::release_abandoned_resolvers(self);
         unsafe { caml_shutdown() }
     }
 }
 
+/// Allocation policy of the OCaml major heap.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OCamlAllocationPolicy {
+    NextFit,
+    FirstFit,
+    BestFit,
+}
+
+impl OCamlAllocationPolicy {
+    pub(crate) fn from_param(param: usize) -> Self {
+        match param {
+            0 => OCamlAllocationPolicy::NextFit,
+            1 => OCamlAllocationPolicy::FirstFit,
+            _ => OCamlAllocationPolicy::BestFit,
+        }
+    }
+
+    pub(crate) fn to_param(self) -> usize {
+        match self {
+            OCamlAllocationPolicy::NextFit => 0,
+            OCamlAllocationPolicy::FirstFit => 1,
+            OCamlAllocationPolicy::BestFit => 2,
+        }
+    }
+}
+
+/// Configures the initialization of the OCaml runtime.
+///
+/// The GC parameters are applied with `Gc.set` once the runtime has been initialized, so they
+/// take precedence over the ones set in the `OCAMLRUNPARAM` environment variable. Parameters
+/// that are not set keep the value given by `OCAMLRUNPARAM`, or OCaml's default.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use ocaml_interop::{OCamlAllocationPolicy, OCamlRuntime};
+///
+/// let cr = OCamlRuntime::builder()
+///     .program_name("my-program")
+///     .args(&["--verbose"])
+///     .minor_heap_size(1024 * 1024)
+///     .space_overhead(120)
+///     .allocation_policy(OCamlAllocationPolicy::BestFit)
+///     .build()
+///     .expect("Failed to initialize the OCaml runtime");
+/// ```
+#[derive(Debug, Clone)]
+pub struct OCamlRuntimeBuilder {
+    program_name: String,
+    args: Vec<String>,
+    minor_heap_size: Option<usize>,
+    space_overhead: Option<usize>,
+    max_overhead: Option<usize>,
+    heap_increment: Option<usize>,
+    allocation_policy: Option<OCamlAllocationPolicy>,
+    stack_limit: Option<usize>,
+    verbose: Option<usize>,
+    backtraces: Option<bool>,
+}
+
+impl Default for OCamlRuntimeBuilder {
+    fn default() -> Self {
+        OCamlRuntimeBuilder {
+            program_name: "ocaml".to_owned(),
+            args: Vec::new(),
+            minor_heap_size: None,
+            space_overhead: None,
+            max_overhead: None,
+            heap_increment: None,
+            allocation_policy: None,
+            stack_limit: None,
+            verbose: None,
+            backtraces: None,
+        }
+    }
+}
+
+impl OCamlRuntimeBuilder {
+    /// Creates a builder with the default configuration.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Sets the program name (`Sys.argv.(0)`). Defaults to `"ocaml"`.
+    pub fn program_name(mut self, name: &str) -> Self {
+        self.program_name = name.to_owned();
+        self
+    }
+
+    /// Appends an argument to the program arguments (`Sys.argv`).
+    pub fn arg(mut self, arg: &str) -> Self {
+        self.args.push(arg.to_owned());
+        self
+    }
+
+    /// Appends arguments to the program arguments (`Sys.argv`).
+    pub fn args<S: AsRef<str>>(mut self, args: &[S]) -> Self {
+        self.args
+            .extend(args.iter().map(|arg| arg.as_ref().to_owned()));
+        self
+    }
+
+    /// Sets the size of the minor heap, in words (`minor_heap_size`).
+    pub fn minor_heap_size(mut self, words: usize) -> Self {
+        self.minor_heap_size = Some(words);
+        self
+    }
+
+    /// Sets the major GC speed, as a percentage of memory wasted in live data (`space_overhead`).
+    pub fn space_overhead(mut self, percent: usize) -> Self {
+        self.space_overhead = Some(percent);
+        self
+    }
+
+    /// Sets the heap compaction trigger, as a percentage of free memory (`max_overhead`).
+    pub fn max_overhead(mut self, percent: usize) -> Self {
+        self.max_overhead = Some(percent);
+        self
+    }
+
+    /// Sets the default size increment of the major heap (`major_heap_increment`).
+    pub fn heap_increment(mut self, increment: usize) -> Self {
+        self.heap_increment = Some(increment);
+        self
+    }
+
+    /// Sets the allocation policy of the major heap (`allocation_policy`).
+    pub fn allocation_policy(mut self, policy: OCamlAllocationPolicy) -> Self {
+        self.allocation_policy = Some(policy);
+        self
+    }
+
+    /// Sets the limit of the size of the stack, in words (`stack_limit`).
+    pub fn stack_limit(mut self, words: usize) -> Self {
+        self.stack_limit = Some(words);
+        self
+    }
+
+    /// Sets the GC messages that are printed to stderr (`verbose`), as a
+    /// bit mask like the one accepted by `Gc.control`.
+    pub fn verbose(mut self, mask: usize) -> Self {
+        self.verbose = Some(mask);
+        self
+    }
+
+    /// Enables or disables the recording of exception backtraces (`Printexc.record_backtrace`).
+    pub fn backtraces(mut self, enabled: bool) -> Self {
+        self.backtraces = Some(enabled);
+        self
+    }
+
+    /// Initializes the OCaml runtime and returns an OCaml runtime handle.
+    pub fn build(self) -> Result<OCamlRuntime, OCamlRuntimeInitError> {
+        self.build_persistent()?;
+        Ok(OCamlRuntime { _private: () })
+    }
+
+    /// Initializes the OCaml runtime.
+    ///
+    /// The program name and arguments are kept alive for the rest of the execution
+    /// of the program, because the OCaml runtime keeps pointers to them.
+    ///
+    /// If the OCaml runtime has already been initialized, the program name and arguments
+    /// are ignored, but the GC parameters are still applied.
+    ///
+    /// # Errors
+    ///
+    /// Only exceptions raised while initializing the OCaml modules linked into the program are
+    /// reported as errors. Failures of the runtime itself (like running out of memory while
+    /// allocating the heap) abort the program, as they do in OCaml programs.
+    pub fn build_persistent(self) -> Result<(), OCamlRuntimeInitError> {
+        let args = std::iter::once(self.program_name.clone())
+            .chain(self.args.iter().cloned())
+            .map(|arg| {
+                CString::new(arg).map_err(|err| {
+                    let arg = String::from_utf8_lossy(&err.into_vec()).into_owned();
+                    OCamlRuntimeInitError::InvalidArgument(arg)
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut c_args: Vec<*const i8> = args
+            .into_iter()
+            .map(|arg| arg.into_raw() as *const i8)
+            .collect();
+        c_args.push(core::ptr::null());
+        let c_args: &'static [*const i8] = Box::leak(c_args.into_boxed_slice());
+
+        let result = unsafe { caml_startup_exn(c_args.as_ptr()) };
+        if is_exception_result(result) {
+            let exception = unsafe { OCamlException::of(extract_exception(result)) };
+            return Err(OCamlRuntimeInitError::Exception(exception.message()));
+        }
+
+        let cr = unsafe { OCamlRuntime::recover_handle() };
+        self.apply_gc_params(cr);
+        if let Some(enabled) = self.backtraces {
+            unsafe { caml_record_backtrace(if enabled { TRUE } else { FALSE }) };
+        }
+        Ok(())
+    }
+
+    fn apply_gc_params(&self, cr: &mut OCamlRuntime) {
+        let mut gc = cr.gc();
+        let mut control = gc.control();
+        let params = [
+            (self.minor_heap_size, &mut control.minor_heap_size),
+            (self.space_overhead, &mut control.space_overhead),
+            (self.max_overhead, &mut control.max_overhead),
+            (self.heap_increment, &mut control.major_heap_increment),
+            (self.stack_limit, &mut control.stack_limit),
+            (self.verbose, &mut control.verbose),
+        ];
+        let mut changed = false;
+        for (value, field) in params {
+            if let Some(value) = value {
+                *field = value;
+                changed = true;
+            }
+        }
+        if let Some(policy) = self.allocation_policy {
+            control.allocation_policy = policy;
+            changed = true;
+        }
+        if changed {
+            gc.set_control(&control);
+        }
+    }
+}
+
 struct OCamlBlockingSection {}
 
 impl OCamlBlockingSection {
@@ -88,3 +357,25 @@ impl Drop for OCamlBlockingSection {
         unsafe { ocaml_sys::caml_leave_blocking_section() };
     }
 }
+
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+// Minimal executor that polls `future` on the current thread, parking it
+// while the future is not ready.
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = Box::pin(future);
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    loop {
+        match Pin::as_mut(&mut future).poll(&mut cx) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => thread::park(),
+        }
+    }
+}
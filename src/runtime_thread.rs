@@ -0,0 +1,186 @@
+// Copyright (c) SimpleStaking and Tezedge Contributors
+// SPDX-License-Identifier: MIT
+
+use crate::{completion::Completion, runtime::OCamlRuntime};
+use core::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use std::{
+    any::Any,
+    panic::{self, AssertUnwindSafe},
+    sync::{mpsc, Arc},
+    thread,
+};
+
+type Job = Box<dyn FnOnce(&mut OCamlRuntime) + Send>;
+
+enum Message {
+    Run(Job),
+    Shutdown,
+}
+
+/// A thread that owns the OCaml runtime.
+///
+/// The OCaml runtime is initialized when the thread is spawned and shut down when
+/// this value is dropped. Calls into OCaml are performed by sending closures to
+/// the thread through an [`OCamlRuntimeThreadHandle`].
+pub struct OCamlRuntimeThread {
+    handle: OCamlRuntimeThreadHandle,
+    thread: Option<thread::JoinHandle<()>>,
+}
+
+impl OCamlRuntimeThread {
+    /// Spawns a new thread, initializes the OCaml runtime on it, and starts
+    /// processing calls.
+    pub fn spawn() -> Self {
+        let (sender, receiver) = mpsc::channel::<Message>();
+        let thread = thread::Builder::new()
+            .name("ocaml-runtime".to_owned())
+            .spawn(move || {
+                let mut cr = OCamlRuntime::init();
+                while let Ok(Message::Run(job)) = receiver.recv() {
+                    job(&mut cr);
+                }
+            })
+            .expect("Failed to spawn the OCaml runtime thread");
+
+        OCamlRuntimeThread {
+            handle: OCamlRuntimeThreadHandle {
+                sender,
+                runtime_thread: thread.thread().id(),
+            },
+            thread: Some(thread),
+        }
+    }
+
+    /// Returns a handle that can be used to perform calls on this runtime thread.
+    pub fn handle(&self) -> OCamlRuntimeThreadHandle {
+        self.handle.clone()
+    }
+}
+
+impl Drop for OCamlRuntimeThread {
+    fn drop(&mut self) {
+        let _ = self.handle.sender.send(Message::Shutdown);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Handle used to perform calls on an [`OCamlRuntimeThread`].
+///
+/// Handles can be cloned and sent to other threads. Calls are performed in the
+/// order in which they are received by the runtime thread.
+#[derive(Clone)]
+pub struct OCamlRuntimeThreadHandle {
+    sender: mpsc::Sender<Message>,
+    runtime_thread: thread::ThreadId,
+}
+
+impl OCamlRuntimeThreadHandle {
+    /// Runs `f` on the runtime thread and blocks until it completes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the runtime thread is not running anymore, and resumes any panic raised by `f`.
+    ///
+    /// Also panics when called from the runtime thread itself (from inside another call),
+    /// because the runtime thread would wait for itself forever. Use the `OCamlRuntime`
+    /// handle passed to the call instead.
+    pub fn call<T, F>(&self, f: F) -> T
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut OCamlRuntime) -> T + Send + 'static,
+    {
+        if thread::current().id() == self.runtime_thread {
+            panic!("OCamlRuntimeThreadHandle::call can't be used from the OCaml runtime thread");
+        }
+        let (sender, receiver) = mpsc::channel();
+        self.send(Box::new(move |cr| {
+            let _ = sender.send(panic::catch_unwind(AssertUnwindSafe(|| f(cr))));
+        }));
+        match receiver.recv() {
+            Ok(result) => unwrap_call_result(result),
+            Err(_) => panic!("OCaml runtime thread stopped before completing the call"),
+        }
+    }
+
+    /// Schedules `f` to be run on the runtime thread, and returns a future that
+    /// resolves to its result.
+    ///
+    /// The returned future doesn't depend on any specific executor, and the calling thread
+    /// is never blocked waiting for the OCaml runtime.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the runtime thread is not running anymore. Polling the returned future
+    /// resumes any panic raised by `f`.
+    pub fn call_async<T, F>(&self, f: F) -> OCamlCall<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut OCamlRuntime) -> T + Send + 'static,
+    {
+        let completion = Arc::new(Completion::new());
+        let completer = OCamlCallCompleter {
+            completion: completion.clone(),
+        };
+        self.send(Box::new(move |cr| {
+            let result = panic::catch_unwind(AssertUnwindSafe(|| f(cr)));
+            completer.complete(result);
+        }));
+        OCamlCall { completion }
+    }
+
+    fn send(&self, job: Job) {
+        self.sender
+            .send(Message::Run(job))
+            .unwrap_or_else(|_| panic!("OCaml runtime thread is not running"))
+    }
+}
+
+type CallResult<T> = Result<T, Box<dyn Any + Send>>;
+
+// Completes the call when the job runs. If the job is dropped without running (because the
+// runtime thread stopped), the call is completed with a panic so that the future doesn't hang.
+struct OCamlCallCompleter<T> {
+    completion: Arc<Completion<CallResult<T>>>,
+}
+
+impl<T> OCamlCallCompleter<T> {
+    fn complete(&self, result: CallResult<T>) {
+        self.completion.complete(result);
+    }
+}
+
+impl<T> Drop for OCamlCallCompleter<T> {
+    fn drop(&mut self) {
+        if self.completion.is_pending() {
+            self.complete(Err(Box::new(
+                "OCaml runtime thread stopped before completing the call",
+            )));
+        }
+    }
+}
+
+/// Future returned by [`OCamlRuntimeThreadHandle::call_async`].
+pub struct OCamlCall<T> {
+    completion: Arc<Completion<CallResult<T>>>,
+}
+
+impl<T> Future for OCamlCall<T> {
+    type Output = T;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<T> {
+        self.completion.poll(cx).map(unwrap_call_result)
+    }
+}
+
+fn unwrap_call_result<T>(result: CallResult<T>) -> T {
+    match result {
+        Ok(value) => value,
+        Err(payload) => panic::resume_unwind(payload),
+    }
+}
@@ -2,8 +2,10 @@
 // SPDX-License-Identifier: MIT
 
 use crate::{
-    error::OCamlFixnumConversionError, memory::OCamlCell, mlvalues::*, FromOCaml, OCamlRef,
-    OCamlRuntime,
+    error::OCamlFixnumConversionError,
+    memory::{alloc_box, alloc_box_with_size, alloc_cons, alloc_ref, box_value_ref, OCamlCell},
+    mlvalues::*,
+    ocaml_frame, to_ocaml, FromOCaml, FromOCamlBorrowed, OCamlRef, OCamlRuntime, ToOCaml,
 };
 use core::{marker::PhantomData, ops::Deref, slice, str};
 use ocaml_sys::{caml_string_length, int_val, val_int};
@@ -58,6 +60,15 @@ impl<'a, T> OCaml<'a, T> {
         }
     }
 
+    // Values of `[@@unboxed]` types are represented directly by their only field
+    #[doc(hidden)]
+    pub unsafe fn unboxed<F>(&self) -> OCaml<'a, F> {
+        OCaml {
+            _marker: PhantomData,
+            raw: self.raw,
+        }
+    }
+
     #[doc(hidden)]
     pub fn is_block(&self) -> bool {
         is_block(self.raw)
@@ -109,6 +120,14 @@ impl<'a, T> OCaml<'a, T> {
     {
         RustT::from_ocaml(*self)
     }
+
+    /// Converts this OCaml value into a Rust value that may borrow from it.
+    pub fn to_rust_borrowed<RustT>(&self) -> RustT
+    where
+        RustT: FromOCamlBorrowed<'a, T>,
+    {
+        RustT::from_ocaml_borrowed(*self)
+    }
 }
 
 impl OCaml<'static, ()> {
@@ -195,6 +214,48 @@ impl<'a> OCaml<'a, OCamlBytes> {
     }
 }
 
+impl<'a, A> OCaml<'a, OCamlRefCell<A>> {
+    /// Allocates a new OCaml reference containing `value` (`ref value`).
+    pub fn new_ref(cr: &'a mut OCamlRuntime, value: OCamlRef<A>) -> Self {
+        alloc_ref(cr, value)
+    }
+
+    /// Returns the value contained in this OCaml reference (`!r`).
+    pub fn get(&self) -> OCaml<'a, A> {
+        unsafe { self.field(0) }
+    }
+}
+
+impl<'a, A: 'static> OCaml<'a, DynBox<A>> {
+    /// Allocates an OCaml custom block that takes ownership of `value`.
+    ///
+    /// `value` will be dropped when the OCaml value is collected by the GC.
+    ///
+    /// # Note
+    ///
+    /// `value` is dropped by the finaliser of the OCaml block, while the GC is running, so
+    /// its `Drop` implementation must not call into the OCaml runtime. This includes dropping an
+    /// [`OCamlGlobalRoot`](crate::OCamlGlobalRoot), which would unregister the root from inside
+    /// the GC: values that own global roots must not be boxed.
+    pub fn box_value(cr: &'a mut OCamlRuntime, value: A) -> Self {
+        alloc_box(cr, value)
+    }
+
+    /// Like [`box_value`](Self::box_value), but also reports to the GC that `size_hint` bytes
+    /// of memory outside of the OCaml heap are owned by `value`.
+    ///
+    /// The GC will then collect more often when many of these values are allocated, instead of
+    /// only considering the size of the OCaml block.
+    pub fn box_value_with_size(cr: &'a mut OCamlRuntime, value: A, size_hint: usize) -> Self {
+        alloc_box_with_size(cr, value, size_hint)
+    }
+
+    /// Returns a reference to the Rust value owned by this OCaml value.
+    pub fn boxed_value(&self) -> &'a A {
+        unsafe { box_value_ref(self.raw) }
+    }
+}
+
 impl<'a> OCaml<'a, OCamlInt> {
     /// Converts an OCaml int to an `i64`.
     pub fn to_i64(&self) -> i64 {
@@ -219,9 +280,17 @@ impl<'a> OCaml<'a, OCamlInt> {
     // The conversion fails if the `i64` value doesn't fit in an OCaml fixnum and
     // an error is returned instead.
     pub fn of_i64(n: i64) -> Result<OCaml<'static, OCamlInt>, OCamlFixnumConversionError> {
-        if n > MAX_FIXNUM as i64 {
+        Self::of_i128(n as i128)
+    }
+
+    /// Creates an OCaml int from an `i128`.
+    ///
+    /// The conversion fails if the `i128` value doesn't fit in an OCaml fixnum and
+    /// an error is returned instead.
+    pub fn of_i128(n: i128) -> Result<OCaml<'static, OCamlInt>, OCamlFixnumConversionError> {
+        if n > MAX_FIXNUM as i128 {
             Err(OCamlFixnumConversionError::InputTooBig(n))
-        } else if n < MIN_FIXNUM as i64 {
+        } else if n < MIN_FIXNUM as i128 {
             Err(OCamlFixnumConversionError::InputTooSmall(n))
         } else {
             Ok(OCaml {
@@ -231,6 +300,20 @@ impl<'a> OCaml<'a, OCamlInt> {
         }
     }
 
+    /// Creates an OCaml int from an `i128`, wrapping around the bounds of OCaml fixnums
+    /// when the value doesn't fit (like OCaml's own integer arithmetic does).
+    pub fn of_i128_wrapping(n: i128) -> OCaml<'static, OCamlInt> {
+        // Only the lower 63 bits survive the tagging shift
+        unsafe { Self::of_i64_unchecked(n as i64) }
+    }
+
+    /// Creates an OCaml int from an `i128`, clamping it to `MIN_FIXNUM..=MAX_FIXNUM`
+    /// when the value doesn't fit.
+    pub fn of_i128_saturating(n: i128) -> OCaml<'static, OCamlInt> {
+        let n = n.clamp(MIN_FIXNUM as i128, MAX_FIXNUM as i128);
+        unsafe { Self::of_i64_unchecked(n as i64) }
+    }
+
     /// Creates an OCaml int from an i32.
     pub fn of_i32(n: i32) -> OCaml<'static, OCamlInt> {
         OCaml {
@@ -314,60 +397,79 @@ impl<'a, A, Err> OCaml<'a, Result<A, Err>> {
     }
 }
 
-impl<'a, A, B> OCaml<'a, (A, B)> {
-    pub fn to_tuple(&self) -> (OCaml<'a, A>, OCaml<'a, B>) {
-        (self.fst(), self.snd())
-    }
-
-    pub fn fst(&self) -> OCaml<'a, A> {
-        unsafe { self.field(0) }
-    }
-
-    pub fn snd(&self) -> OCaml<'a, B> {
-        unsafe { self.field(1) }
-    }
+/// Gives access to the element at index `I` of OCaml tuples.
+///
+/// Implemented for tuples of up to 12 elements, see [`OCaml::element`].
+pub trait OCamlTupleElement<const I: usize> {
+    type Element;
 }
 
-impl<'a, A, B, C> OCaml<'a, (A, B, C)> {
-    pub fn to_tuple(&self) -> (OCaml<'a, A>, OCaml<'a, B>, OCaml<'a, C>) {
-        (self.fst(), self.snd(), self.tuple_3())
-    }
-
-    pub fn fst(&self) -> OCaml<'a, A> {
-        unsafe { self.field(0) }
-    }
-
-    pub fn snd(&self) -> OCaml<'a, B> {
-        unsafe { self.field(1) }
-    }
-
-    pub fn tuple_3(&self) -> OCaml<'a, C> {
-        unsafe { self.field(2) }
+impl<'a, T> OCaml<'a, T> {
+    /// Returns the element at index `I` (starting from 0, like Rust's `tuple.I`) of an OCaml tuple.
+    pub fn element<const I: usize>(&self) -> OCaml<'a, T::Element>
+    where
+        T: OCamlTupleElement<I>,
+    {
+        unsafe { self.field(I) }
     }
 }
 
-impl<'a, A, B, C, D> OCaml<'a, (A, B, C, D)> {
-    pub fn to_tuple(&self) -> (OCaml<'a, A>, OCaml<'a, B>, OCaml<'a, C>, OCaml<'a, D>) {
-        (self.fst(), self.snd(), self.tuple_3(), self.tuple_4())
-    }
+macro_rules! impl_tuple_accessors {
+    ($($typ:ident $accessor:ident $index:tt),+ $(,)?) => {
+        impl<'a, $($typ),+> OCaml<'a, ($($typ),+)> {
+            pub fn to_tuple(&self) -> ($(OCaml<'a, $typ>),+) {
+                ($(self.$accessor()),+)
+            }
+
+            $(
+                pub fn $accessor(&self) -> OCaml<'a, $typ> {
+                    unsafe { self.field($index) }
+                }
+            )+
+        }
 
-    pub fn fst(&self) -> OCaml<'a, A> {
-        unsafe { self.field(0) }
-    }
+        impl_tuple_accessors!(@elements [$($typ),+] $($typ $index),+);
+    };
 
-    pub fn snd(&self) -> OCaml<'a, B> {
-        unsafe { self.field(1) }
-    }
+    (@elements [$($all:ident),+] $typ:ident $index:tt $(, $($rest:tt)*)?) => {
+        impl<$($all),+> OCamlTupleElement<$index> for ($($all),+) {
+            type Element = $typ;
+        }
 
-    pub fn tuple_3(&self) -> OCaml<'a, C> {
-        unsafe { self.field(2) }
-    }
+        impl_tuple_accessors!(@elements [$($all),+] $($($rest)*)?);
+    };
 
-    pub fn tuple_4(&self) -> OCaml<'a, D> {
-        unsafe { self.field(3) }
-    }
+    (@elements [$($all:ident),+]) => {};
 }
 
+impl_tuple_accessors!(A fst 0, B snd 1);
+impl_tuple_accessors!(A fst 0, B snd 1, C tuple_3 2);
+impl_tuple_accessors!(A fst 0, B snd 1, C tuple_3 2, D tuple_4 3);
+impl_tuple_accessors!(A fst 0, B snd 1, C tuple_3 2, D tuple_4 3, E tuple_5 4);
+impl_tuple_accessors!(A fst 0, B snd 1, C tuple_3 2, D tuple_4 3, E tuple_5 4, F tuple_6 5);
+impl_tuple_accessors!(
+    A fst 0, B snd 1, C tuple_3 2, D tuple_4 3, E tuple_5 4, F tuple_6 5, G tuple_7 6,
+);
+impl_tuple_accessors!(
+    A fst 0, B snd 1, C tuple_3 2, D tuple_4 3, E tuple_5 4, F tuple_6 5, G tuple_7 6, H tuple_8 7,
+);
+impl_tuple_accessors!(
+    A fst 0, B snd 1, C tuple_3 2, D tuple_4 3, E tuple_5 4, F tuple_6 5, G tuple_7 6, H tuple_8 7,
+    I tuple_9 8,
+);
+impl_tuple_accessors!(
+    A fst 0, B snd 1, C tuple_3 2, D tuple_4 3, E tuple_5 4, F tuple_6 5, G tuple_7 6, H tuple_8 7,
+    I tuple_9 8, J tuple_10 9,
+);
+impl_tuple_accessors!(
+    A fst 0, B snd 1, C tuple_3 2, D tuple_4 3, E tuple_5 4, F tuple_6 5, G tuple_7 6, H tuple_8 7,
+    I tuple_9 8, J tuple_10 9, K tuple_11 10,
+);
+impl_tuple_accessors!(
+    A fst 0, B snd 1, C tuple_3 2, D tuple_4 3, E tuple_5 4, F tuple_6 5, G tuple_7 6, H tuple_8 7,
+    I tuple_9 8, J tuple_10 9, K tuple_11 10, L tuple_12 11,
+);
+
 impl<'a, A> OCaml<'a, OCamlList<A>> {
     /// Returns an OCaml nil (empty list) value.
     pub fn nil() -> Self {
@@ -408,4 +510,50 @@ impl<'a, A> OCaml<'a, OCamlList<A>> {
             Some(unsafe { (self.field(0), self.field(1)) })
         }
     }
+
+    /// Returns an iterator over the elements of an OCaml list.
+    ///
+    /// Iterating doesn't allocate nor recurse, so lists of any length can be traversed.
+    pub fn iter(&self) -> OCamlListIter<'a, A> {
+        OCamlListIter { current: *self }
+    }
+
+    /// Builds an OCaml list containing the elements produced by `elements`, in order.
+    ///
+    /// The list is built tail-first by iterating `elements` backwards, without recursion
+    /// and using a constant number of roots, so lists of any length can be built.
+    ///
+    /// This is how iterators and collections without a [`ToOCaml`] implementation are converted,
+    /// since implementing it for every `IntoIterator` would overlap with the other implementations.
+    pub fn of_iter<I>(cr: &'a mut OCamlRuntime, elements: I) -> Self
+    where
+        I: IntoIterator,
+        I::IntoIter: DoubleEndedIterator,
+        I::Item: ToOCaml<A>,
+    {
+        ocaml_frame!(cr, (result_root, elt_root), {
+            let mut result = result_root.keep(OCaml::nil());
+            for elt in elements.into_iter().rev() {
+                let elt = to_ocaml!(cr, elt, elt_root);
+                let cons = alloc_cons(cr, elt, result);
+                result = result_root.keep(cons);
+            }
+            cr.get(result)
+        })
+    }
+}
+
+/// Iterator over the elements of an OCaml list, returned by `OCaml::<OCamlList<T>>::iter`.
+pub struct OCamlListIter<'a, A> {
+    current: OCaml<'a, OCamlList<A>>,
+}
+
+impl<'a, A> Iterator for OCamlListIter<'a, A> {
+    type Item = OCaml<'a, A>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (hd, tl) = self.current.uncons()?;
+        self.current = tl;
+        Some(hd)
+    }
 }
@@ -0,0 +1,190 @@
+// Copyright (c) SimpleStaking and Tezedge Contributors
+// SPDX-License-Identifier: MIT
+
+use crate::{
+    memory::{alloc_some, OCamlCell},
+    mlvalues::{wosize_val, OCamlEphemeron, OCamlWeak, RawOCaml, NONE},
+    OCaml, OCamlRef, OCamlRuntime,
+};
+use ocaml_sys::{int_val, val_int};
+
+// Primitives used by the `Weak` and `Ephemeron` modules of OCaml's standard library.
+extern "C" {
+    fn caml_weak_create(len: RawOCaml) -> RawOCaml;
+    fn caml_weak_set(ar: RawOCaml, n: RawOCaml, el: RawOCaml) -> RawOCaml;
+    fn caml_weak_get(ar: RawOCaml, n: RawOCaml) -> RawOCaml;
+    fn caml_weak_check(ar: RawOCaml, n: RawOCaml) -> RawOCaml;
+    fn caml_ephe_create(len: RawOCaml) -> RawOCaml;
+    fn caml_ephe_set_key(ar: RawOCaml, n: RawOCaml, el: RawOCaml) -> RawOCaml;
+    fn caml_ephe_unset_key(ar: RawOCaml, n: RawOCaml) -> RawOCaml;
+    fn caml_ephe_get_key(ar: RawOCaml, n: RawOCaml) -> RawOCaml;
+    fn caml_ephe_check_key(ar: RawOCaml, n: RawOCaml) -> RawOCaml;
+    fn caml_ephe_set_data(ar: RawOCaml, el: RawOCaml) -> RawOCaml;
+    fn caml_ephe_unset_data(ar: RawOCaml) -> RawOCaml;
+    fn caml_ephe_get_data(ar: RawOCaml) -> RawOCaml;
+    fn caml_ephe_check_data(ar: RawOCaml) -> RawOCaml;
+}
+
+// Weak arrays and ephemerons are the same kind of block, the first two fields
+// are used by the GC and the data, and the keys come after them.
+const EPHE_FIRST_KEY: usize = 2;
+
+unsafe fn ephe_len(raw: RawOCaml) -> usize {
+    wosize_val(raw) - EPHE_FIRST_KEY
+}
+
+fn check_index(len: usize, i: usize) {
+    assert!(
+        i < len,
+        "index out of bounds: the len is {} but the index is {}",
+        len,
+        i
+    );
+}
+
+impl<'a, A> OCaml<'a, OCamlWeak<A>> {
+    /// Allocates a new weak array of length `len`, with all its slots empty (`Weak.create`).
+    pub fn create_weak(cr: &'a mut OCamlRuntime, len: usize) -> Self {
+        unsafe { OCaml::new(cr, caml_weak_create(val_int(len as isize))) }
+    }
+
+    /// Returns the length of the weak array.
+    pub fn len(&self) -> usize {
+        unsafe { ephe_len(self.raw()) }
+    }
+
+    /// Returns true if the length of the weak array is `0`.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<A> OCamlCell<OCamlWeak<A>> {
+    /// Returns the length of the weak array.
+    pub fn len(&self, cr: &OCamlRuntime) -> usize {
+        cr.get(self).len()
+    }
+
+    /// Sets slot `i` to the value `value`, which will not be kept alive by the array (`Weak.set`).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i` is out of bounds.
+    pub fn set(&self, cr: &mut OCamlRuntime, i: usize, value: OCamlRef<A>) {
+        check_index(self.len(cr), i);
+        let some = alloc_some(cr, value);
+        unsafe { caml_weak_set(self.get_raw(), val_int(i as isize), some.raw()) };
+    }
+
+    /// Empties slot `i` (`Weak.set` with `None`).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i` is out of bounds.
+    pub fn unset(&self, cr: &mut OCamlRuntime, i: usize) {
+        check_index(self.len(cr), i);
+        unsafe { caml_weak_set(self.get_raw(), val_int(i as isize), NONE) };
+    }
+
+    /// Returns the value in slot `i`, or `None` if the slot is empty or the value has
+    /// been collected (`Weak.get`).
+    ///
+    /// The returned value is kept alive for as long as it is rooted.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i` is out of bounds.
+    pub fn get<'a>(&self, cr: &'a mut OCamlRuntime, i: usize) -> OCaml<'a, Option<A>> {
+        check_index(self.len(cr), i);
+        unsafe { OCaml::new(cr, caml_weak_get(self.get_raw(), val_int(i as isize))) }
+    }
+
+    /// Returns true if slot `i` contains a value (`Weak.check`).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i` is out of bounds.
+    pub fn check(&self, cr: &OCamlRuntime, i: usize) -> bool {
+        check_index(self.len(cr), i);
+        unsafe { int_val(caml_weak_check(self.get_raw(), val_int(i as isize))) != 0 }
+    }
+}
+
+impl<'a, K, D> OCaml<'a, OCamlEphemeron<K, D>> {
+    /// Allocates a new ephemeron with `keys` empty keys and no data.
+    pub fn create_ephemeron(cr: &'a mut OCamlRuntime, keys: usize) -> Self {
+        unsafe { OCaml::new(cr, caml_ephe_create(val_int(keys as isize))) }
+    }
+
+    /// Returns the number of keys of the ephemeron.
+    pub fn keys_len(&self) -> usize {
+        unsafe { ephe_len(self.raw()) }
+    }
+}
+
+impl<K, D> OCamlCell<OCamlEphemeron<K, D>> {
+    /// Returns the number of keys of the ephemeron.
+    pub fn keys_len(&self, cr: &OCamlRuntime) -> usize {
+        cr.get(self).keys_len()
+    }
+
+    /// Sets key `i` of the ephemeron.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i` is out of bounds.
+    pub fn set_key(&self, cr: &mut OCamlRuntime, i: usize, key: OCamlRef<K>) {
+        check_index(self.keys_len(cr), i);
+        unsafe { caml_ephe_set_key(self.get_raw(), val_int(i as isize), key.get_raw()) };
+    }
+
+    /// Empties key `i` of the ephemeron.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i` is out of bounds.
+    pub fn unset_key(&self, cr: &mut OCamlRuntime, i: usize) {
+        check_index(self.keys_len(cr), i);
+        unsafe { caml_ephe_unset_key(self.get_raw(), val_int(i as isize)) };
+    }
+
+    /// Returns key `i` of the ephemeron, or `None` if it is empty or has been collected.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i` is out of bounds.
+    pub fn get_key<'a>(&self, cr: &'a mut OCamlRuntime, i: usize) -> OCaml<'a, Option<K>> {
+        check_index(self.keys_len(cr), i);
+        unsafe { OCaml::new(cr, caml_ephe_get_key(self.get_raw(), val_int(i as isize))) }
+    }
+
+    /// Returns true if key `i` of the ephemeron is set.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i` is out of bounds.
+    pub fn check_key(&self, cr: &OCamlRuntime, i: usize) -> bool {
+        check_index(self.keys_len(cr), i);
+        unsafe { int_val(caml_ephe_check_key(self.get_raw(), val_int(i as isize))) != 0 }
+    }
+
+    /// Sets the data of the ephemeron, which is kept alive for as long as all keys are alive.
+    pub fn set_data(&self, _cr: &mut OCamlRuntime, data: OCamlRef<D>) {
+        unsafe { caml_ephe_set_data(self.get_raw(), data.get_raw()) };
+    }
+
+    /// Empties the data of the ephemeron.
+    pub fn unset_data(&self, _cr: &mut OCamlRuntime) {
+        unsafe { caml_ephe_unset_data(self.get_raw()) };
+    }
+
+    /// Returns the data of the ephemeron, or `None` if it is empty or one of the keys has been collected.
+    pub fn get_data<'a>(&self, cr: &'a mut OCamlRuntime) -> OCaml<'a, Option<D>> {
+        unsafe { OCaml::new(cr, caml_ephe_get_data(self.get_raw())) }
+    }
+
+    /// Returns true if the data of the ephemeron is set.
+    pub fn check_data(&self, _cr: &OCamlRuntime) -> bool {
+        unsafe { int_val(caml_ephe_check_data(self.get_raw())) != 0 }
+    }
+}
@@ -0,0 +1,165 @@
+// Copyright (c) SimpleStaking and Tezedge Contributors
+// SPDX-License-Identifier: MIT
+
+use crate::{
+    conv::{FromOCaml, ToOCaml},
+    memory::alloc_tuple,
+    mlvalues::{field_val, MAX_FIXNUM, MIN_FIXNUM},
+    ocaml_frame,
+    runtime::OCamlRuntime,
+    to_ocaml,
+    value::OCaml,
+};
+use core::{convert::TryFrom, mem::size_of, slice};
+use num_bigint::{BigInt, BigUint, Sign};
+use num_rational::BigRational;
+use ocaml_sys::{caml_alloc_custom, custom_operations, int_val, val_int, Char};
+
+extern "C" {
+    fn caml_find_custom_operations(ident: *const Char) -> *mut custom_operations;
+}
+
+/// `OCaml<OCamlZ>` is a reference to a Zarith `Z.t` (arbitrary-precision integer) value.
+///
+/// # Note
+///
+/// Requires the Zarith library to be linked into the program.
+pub struct OCamlZ {}
+
+/// `OCaml<OCamlQ>` is a reference to a Zarith `Q.t` (arbitrary-precision rational) value.
+///
+/// # Note
+///
+/// Requires the Zarith library to be linked into the program.
+pub struct OCamlQ {}
+
+// Integers that don't fit in an OCaml fixnum are stored by Zarith in custom blocks
+// containing a header word (sign bit + number of limbs) followed by the limbs of the
+// magnitude, least significant first. Limbs are as wide as OCaml values.
+const Z_IDENTIFIER: &[u8] = b"_z\0";
+const Z_SIGN_MASK: usize = 1 << (usize::BITS - 1);
+const Z_SIZE_MASK: usize = !Z_SIGN_MASK;
+
+#[cfg(target_pointer_width = "64")]
+fn limbs(n: &BigUint) -> Vec<usize> {
+    n.iter_u64_digits().map(|limb| limb as usize).collect()
+}
+
+#[cfg(target_pointer_width = "32")]
+fn limbs(n: &BigUint) -> Vec<usize> {
+    n.iter_u32_digits().map(|limb| limb as usize).collect()
+}
+
+#[cfg(target_pointer_width = "64")]
+fn of_limbs(limbs: &[usize]) -> BigUint {
+    let mut digits = Vec::with_capacity(limbs.len() * 2);
+    for &limb in limbs {
+        digits.push(limb as u32);
+        digits.push((limb >> 32) as u32);
+    }
+    BigUint::new(digits)
+}
+
+#[cfg(target_pointer_width = "32")]
+fn of_limbs(limbs: &[usize]) -> BigUint {
+    BigUint::new(limbs.iter().map(|&limb| limb as u32).collect())
+}
+
+fn alloc_z<'a>(cr: &'a mut OCamlRuntime, n: &BigInt) -> OCaml<'a, OCamlZ> {
+    if let Ok(n) = i64::try_from(n) {
+        if n >= MIN_FIXNUM as i64 && n <= MAX_FIXNUM as i64 {
+            return unsafe { OCaml::new(cr, val_int(n as isize)) };
+        }
+    }
+    let limbs = limbs(n.magnitude());
+    let ops = unsafe { caml_find_custom_operations(Z_IDENTIFIER.as_ptr() as *const Char) };
+    if ops.is_null() {
+        panic!("Zarith custom operations not found, the Zarith library has to be linked");
+    }
+    unsafe {
+        let z = caml_alloc_custom(ops, (1 + limbs.len()) * size_of::<usize>(), 0, 1);
+        let data = field_val(z, 1) as *mut usize;
+        let sign = if n.sign() == Sign::Minus {
+            Z_SIGN_MASK
+        } else {
+            0
+        };
+        *data = limbs.len() | sign;
+        data.add(1)
+            .copy_from_nonoverlapping(limbs.as_ptr(), limbs.len());
+        OCaml::new(cr, z)
+    }
+}
+
+fn read_z(v: OCaml<OCamlZ>) -> BigInt {
+    if v.is_long() {
+        return BigInt::from(unsafe { int_val(v.raw()) } as i64);
+    }
+    unsafe {
+        let data = field_val(v.raw(), 1) as *const usize;
+        let head = *data;
+        let limbs = slice::from_raw_parts(data.add(1), head & Z_SIZE_MASK);
+        let sign = if head & Z_SIGN_MASK != 0 {
+            Sign::Minus
+        } else {
+            Sign::Plus
+        };
+        BigInt::from_biguint(sign, of_limbs(limbs))
+    }
+}
+
+unsafe impl ToOCaml<OCamlZ> for BigInt {
+    fn to_ocaml<'a>(&self, cr: &'a mut OCamlRuntime) -> OCaml<'a, OCamlZ> {
+        alloc_z(cr, self)
+    }
+}
+
+unsafe impl ToOCaml<OCamlZ> for BigUint {
+    fn to_ocaml<'a>(&self, cr: &'a mut OCamlRuntime) -> OCaml<'a, OCamlZ> {
+        alloc_z(cr, &BigInt::from(self.clone()))
+    }
+}
+
+unsafe impl FromOCaml<OCamlZ> for BigInt {
+    fn from_ocaml(v: OCaml<OCamlZ>) -> Self {
+        read_z(v)
+    }
+}
+
+unsafe impl FromOCaml<OCamlZ> for BigUint {
+    /// # Panics
+    ///
+    /// Panics if the integer is negative.
+    fn from_ocaml(v: OCaml<OCamlZ>) -> Self {
+        read_z(v)
+            .to_biguint()
+            .expect("negative Z.t value can't be converted into BigUint")
+    }
+}
+
+unsafe impl ToOCaml<OCamlQ> for BigRational {
+    fn to_ocaml<'a>(&self, cr: &'a mut OCamlRuntime) -> OCaml<'a, OCamlQ> {
+        // `BigRational` values are always normalized like `Q.t` values are,
+        // so the numerator and denominator can be stored as they are.
+        ocaml_frame!(cr, (num, den), {
+            let num = to_ocaml!(cr, self.numer(), num);
+            let den = to_ocaml!(cr, self.denom(), den);
+            let q = unsafe { alloc_tuple::<OCamlZ, OCamlZ>(cr, num, den).raw() };
+            unsafe { OCaml::new(cr, q) }
+        })
+    }
+}
+
+unsafe impl FromOCaml<OCamlQ> for BigRational {
+    /// # Panics
+    ///
+    /// Panics if the rational is infinite or undefined (`Q.inf`, `Q.minus_inf` or `Q.undef`).
+    fn from_ocaml(v: OCaml<OCamlQ>) -> Self {
+        let num = read_z(unsafe { v.field(0) });
+        let den = read_z(unsafe { v.field(1) });
+        if den == BigInt::from(0) {
+            panic!("infinite or undefined Q.t value can't be converted into BigRational");
+        }
+        BigRational::new_raw(num, den)
+    }
+}
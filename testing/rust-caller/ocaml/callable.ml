@@ -64,6 +64,136 @@ let stringify_polymorphic_variant = function
   | `RotateRight -> "`RotateRight"
   | `Step n -> Printf.sprintf "`Step(%d)" n
 
+type shape =
+  | Point
+  | Circle of float
+  | Rect of { width: int; height: int }
+
+let stringify_shape = function
+  | Point -> "Point"
+  | Circle r -> Printf.sprintf "Circle(%.1f)" r
+  | Rect { width; height } -> Printf.sprintf "Rect { width = %d; height = %d }" width height
+
+let make_rect width height = Rect { width; height }
+
+type _ term =
+  | Int : int -> int term
+  | Bool : bool -> bool term
+  | If : { cond: bool term; then_: 'a term; else_: 'a term } -> 'a term
+  | Pair : 'a term * 'b term -> ('a * 'b) term
+
+let rec stringify_term : type a. a term -> string = function
+  | Int n -> string_of_int n
+  | Bool b -> string_of_bool b
+  | If { cond; then_; else_ } ->
+    Printf.sprintf "if %s then %s else %s"
+      (stringify_term cond) (stringify_term then_) (stringify_term else_)
+  | Pair (fst, snd) -> Printf.sprintf "(%s, %s)" (stringify_term fst) (stringify_term snd)
+
+let make_pair_term n b = Pair (Int n, If { cond = Bool b; then_ = Bool false; else_ = Bool true })
+
+type event = ..
+
+type event +=
+  | Started
+  | Progress of int
+  | Failed of { code: int; reason: string }
+  | Plugin_loaded of string
+
+let stringify_event = function
+  | Started -> "Started"
+  | Progress n -> Printf.sprintf "Progress(%d)" n
+  | Failed { code; reason } -> Printf.sprintf "Failed { code = %d; reason = %s }" code reason
+  | Plugin_loaded name -> Printf.sprintf "Plugin_loaded(%s)" name
+  | _ -> "Unknown"
+
+let make_failed_event code reason = Failed { code; reason }
+
+let make_plugin_event () = Plugin_loaded "metrics"
+
+let register_plugin_event () =
+  Callback.register "event.Plugin_loaded" (Obj.Extension_constructor.of_val (Plugin_loaded ""))
+
+exception Plugin_error of string
+
+let make_plugin_error message = Plugin_error message
+
+type user_id = { id: int } [@@unboxed]
+
+let next_user_id { id } = { id = id + 1 }
+
+type meters = Meters of float [@@unboxed]
+
+let double_meters (Meters m) = Meters (m *. 2.)
+
+type 'a page = { items: 'a list; next: string option }
+
+let make_page items = { items; next = Some (string_of_int (List.length items)) }
+
+type 'a tree =
+  | Leaf
+  | Node of 'a tree * 'a * 'a tree
+
+let rec sum_tree = function
+  | Leaf -> 0
+  | Node (left, value, right) -> sum_tree left + value + sum_tree right
+
+let lazy_length s = lazy (String.length s)
+
+let lazy_failure message = lazy (failwith message)
+
+let force_lazy_int l = Lazy.force l
+
+type settings = { name: string; limits: (int * int) Lazy.t }
+
+let make_settings name = { name; limits = lazy (0, String.length name) }
+
+class counter initial = object
+  val mutable count = initial
+  method get = count
+  method add n = count <- count + n; count
+  method describe prefix suffix = prefix ^ string_of_int count ^ suffix
+  method add3 a b c = count + a + b + c
+  method add4 a b c d = count + a + b + c + d
+end
+
+let make_counter initial = new counter initial
+
+let counter_id (counter : counter) = Oo.id counter
+
+let register_late_value (n : int) = Callback.register "late_value" n
+
+let register_late_function () =
+  Callback.register "late_function" (fun n -> n * 3)
+
+let lwt_double p = Lwt.map (fun x -> x * 2) p
+
+let apply_to_ten f = f 10
+
+let sys_argv () = Array.to_list Sys.argv
+
+type tally = { mutable label: string; mutable total: int }
+
+let make_tally label = { label; total = 0 }
+
+let describe_tally { label; total } = label ^ "=" ^ string_of_int total
+
+type point = { mutable x: float; mutable y: float }
+
+let make_point x y = { x; y }
+
+let describe_point { x; y } = Printf.sprintf "(%.1f, %.1f)" x y
+
+type message = { topic: string; body: bytes; priority: int; tags: string list }
+
+let make_message topic priority =
+  let body = Bytes.of_string (String.uppercase_ascii topic) in
+  { topic; body; priority; tags = ["breaking"; topic] }
+
+let make_int_ref n = ref n
+
+let deref_int r = !r
+
 let raises_message_exception msg = failwith msg
 
 let raises_nonblock_exception () = raise Not_found
@@ -72,6 +202,17 @@ exception WithInt of int
 
 let raises_nonmessage_exception () = raise (WithInt 10)
 
+let z_to_string z = Z.to_string z
+
+let z_of_string s = Z.of_string s
+
+let q_to_string q = Q.to_string q
+
+module StringMap = Map.Make (String)
+module StringSet = Set.Make (String)
+
+let string_map_of_list bindings = StringMap.of_seq (List.to_seq bindings)
+
 let () =
   Callback.register "increment_bytes" increment_bytes;
   Callback.register "decrement_bytes" decrement_bytes;
@@ -84,6 +225,48 @@ let () =
   Callback.register "stringify_record" stringify_record;
   Callback.register "stringify_variant" stringify_variant;
   Callback.register "stringify_polymorphic_variant" stringify_polymorphic_variant;
+  Callback.register "stringify_shape" stringify_shape;
+  Callback.register "make_rect" make_rect;
+  Callback.register "stringify_event" stringify_event;
+  Callback.register "make_failed_event" make_failed_event;
+  Callback.register "make_plugin_event" make_plugin_event;
+  Callback.register "register_plugin_event" register_plugin_event;
+  Callback.register "stringify_plugin_event" stringify_event;
+  Callback.register "make_plugin_error" make_plugin_error;
+  Callback.register "next_user_id" next_user_id;
+  Callback.register "double_meters" double_meters;
+  Callback.register "make_page" make_page;
+  Callback.register "sum_tree" sum_tree;
+  Callback.register "lazy_length" lazy_length;
+  Callback.register "lazy_failure" lazy_failure;
+  Callback.register "force_lazy_int" force_lazy_int;
+  Callback.register "make_counter" make_counter;
+  Callback.register "counter_id" counter_id;
+  Callback.register "register_late_value" register_late_value;
+  Callback.register "register_late_function" register_late_function;
+  Callback.register "lwt_double" lwt_double;
+  Callback.register "apply_to_ten" apply_to_ten;
+  Callback.register "sys_argv" sys_argv;
+  Callback.register "make_tally" make_tally;
+  Callback.register "describe_tally" describe_tally;
+  Callback.register "make_point" make_point;
+  Callback.register "describe_point" describe_point;
+  Callback.register "make_int_ref" make_int_ref;
+  Callback.register "deref_int" deref_int;
+  Callback.register "make_message" make_message;
+  Callback.register "stringify_term" stringify_term;
+  Callback.register "make_pair_term" make_pair_term;
+  Callback.register "make_settings" make_settings;
+  Callback.register "primes" [2; 3; 5; 7];
+  Callback.register "event.Started" (Obj.Extension_constructor.of_val Started);
+  Callback.register "event.Progress" (Obj.Extension_constructor.of_val (Progress 0));
+  Callback.register "event.Failed" (Obj.Extension_constructor.of_val (Failed { code = 0; reason = "" }));
+  Callback.register_exception "Plugin_error" (Plugin_error "");
   Callback.register "raises_message_exception" raises_message_exception;
   Callback.register "raises_nonmessage_exception" raises_nonmessage_exception;
-  Callback.register "raises_nonblock_exception" raises_nonblock_exception;
\ No newline at end of file
+  Callback.register "raises_nonblock_exception" raises_nonblock_exception;
+  Callback.register "z_to_string" z_to_string;
+  Callback.register "z_of_string" z_of_string;
+  Callback.register "q_to_string" q_to_string;
+  Callback.register "string_map_of_list" string_map_of_list;
+  Callback.register "string_set_of_list" StringSet.of_list;
@@ -3,12 +3,24 @@
 
 extern crate ocaml_interop;
 
-use ocaml_interop::{ocaml_frame, to_ocaml, OCaml, OCamlBytes, OCamlRuntime, ToOCaml};
+use ocaml_interop::{
+    ocaml_frame, to_ocaml, OCaml, OCamlBytes, OCamlFloat, OCamlFunction, OCamlInt, OCamlKey,
+    OCamlLazy, OCamlList, OCamlLwt, OCamlNamedValue, OCamlRef, OCamlRuntime, OCamlRuntimeInitError,
+    OCamlRuntimeThread, ToOCaml,
+};
+use std::cmp::Ordering;
+use std::collections::{BTreeSet, HashSet};
+use std::{panic, thread};
 
 mod ocaml {
+    use std::marker::PhantomData;
+
     use ocaml_interop::{
-        impl_to_ocaml_record, impl_to_ocaml_variant, ocaml, OCamlFloat, OCamlInt, OCamlInt32,
-        OCamlInt64, OCamlList,
+        impl_conv_ocaml_extensible_variant, impl_conv_ocaml_record, impl_conv_ocaml_variant,
+        impl_from_ocaml_extensible_variant, impl_from_ocaml_record,
+        impl_to_ocaml_polymorphic_variant, impl_to_ocaml_record, impl_to_ocaml_variant, ocaml,
+        FromOCaml, OCamlBytes, OCamlFloat, OCamlFunction, OCamlInt, OCamlInt32, OCamlInt64,
+        OCamlLazy, OCamlList, OCamlLwt, OCamlObject, OCamlQ, OCamlRefCell, OCamlZ, ToOCaml,
     };
 
     pub struct TestRecord {
@@ -26,6 +38,108 @@ mod ocaml {
         RotateRight,
     }
 
+    pub enum PolymorphicMovement {
+        Step(i64),
+        RotateLeft,
+        RotateRight,
+    }
+
+    #[derive(Debug, PartialEq)]
+    pub enum Shape {
+        Point,
+        Circle(f64),
+        // Declared in a different order than in OCaml
+        Rect { height: i64, width: i64 },
+    }
+
+    // Maps all the instances of the `'a term` GADT
+    #[derive(Debug, PartialEq)]
+    pub enum Term {
+        Int(i64),
+        Bool(bool),
+        If {
+            cond: Box<Term>,
+            then_: Box<Term>,
+            else_: Box<Term>,
+        },
+        Pair(Box<Term>, Box<Term>),
+    }
+
+    #[derive(Debug, PartialEq)]
+    pub enum Event {
+        Started,
+        Progress(i64),
+        Failed { code: i64, reason: String },
+    }
+
+    // Its constructor is only registered by `register_plugin_event`
+    #[derive(Debug, PartialEq)]
+    pub enum PluginEvent {
+        Loaded(String),
+    }
+
+    #[derive(Debug, PartialEq)]
+    pub enum PluginError {
+        Error(String),
+    }
+
+    #[derive(Debug, PartialEq)]
+    pub struct UserId(pub i64);
+
+    #[derive(Debug, PartialEq)]
+    pub enum Meters {
+        Meters(f64),
+    }
+
+    #[derive(Debug, PartialEq)]
+    pub struct Page<T> {
+        pub items: Vec<T>,
+        pub next: Option<String>,
+    }
+
+    pub struct OCamlPage<T> {
+        _marker: PhantomData<T>,
+    }
+
+    pub enum Tree<T> {
+        Leaf,
+        Node(Box<Tree<T>>, T, Box<Tree<T>>),
+    }
+
+    pub struct OCamlTree<T> {
+        _marker: PhantomData<T>,
+    }
+
+    #[derive(Debug, PartialEq)]
+    pub struct Tally {
+        pub label: String,
+        pub total: i64,
+    }
+
+    pub struct OCamlMessage {}
+
+    // Borrows its strings from the OCaml record
+    pub struct MessageRef<'a> {
+        pub topic: &'a str,
+        pub body: &'a [u8],
+        pub priority: i64,
+        pub tags: Vec<&'a str>,
+    }
+
+    #[derive(Debug, PartialEq)]
+    pub struct Settings {
+        pub name: String,
+        pub limits: (i64, i64),
+    }
+
+    // Only has float fields, which OCaml stores unboxed, so it is only
+    // modified with setters and never converted
+    #[allow(dead_code)]
+    pub struct Point {
+        pub x: f64,
+        pub y: f64,
+    }
+
     impl_to_ocaml_record! {
         TestRecord {
             i: OCamlInt,
@@ -45,7 +159,122 @@ mod ocaml {
         }
     }
 
+    impl_conv_ocaml_variant! {
+        Shape {
+            Shape::Point,
+            Shape::Circle(radius: OCamlFloat),
+            Shape::Rect { width: OCamlInt, height: OCamlInt },
+        }
+    }
+
+    impl_conv_ocaml_variant! {
+        Term {
+            Term::Int(n: OCamlInt),
+            Term::Bool(b: bool),
+            Term::If { cond: Term, then_: Term, else_: Term },
+            Term::Pair(fst: Term, snd: Term),
+        }
+    }
+
+    impl_conv_ocaml_extensible_variant! {
+        Event {
+            "event.Started" => Event::Started,
+            "event.Progress" => Event::Progress(percent: OCamlInt),
+            "event.Failed" => Event::Failed { code: OCamlInt, reason: String },
+        }
+    }
+
+    impl_conv_ocaml_extensible_variant! {
+        PluginEvent {
+            "event.Plugin_loaded" => PluginEvent::Loaded(name: String),
+        }
+    }
+
+    impl_from_ocaml_extensible_variant! {
+        PluginError {
+            "Plugin_error" => PluginError::Error(message: String),
+        }
+    }
+
+    impl_conv_ocaml_record! {
+        #[unboxed]
+        UserId(id: OCamlInt)
+    }
+
+    impl_conv_ocaml_variant! {
+        #[unboxed]
+        Meters {
+            Meters::Meters(value: OCamlFloat),
+        }
+    }
+
+    impl_conv_ocaml_record! {
+        impl<T, OT> Page<T> => OCamlPage<OT>
+        where
+            T: ToOCaml<OT>,
+            T: FromOCaml<OT>,
+        {
+            items: OCamlList<OT>,
+            next: Option<String>,
+        }
+    }
+
+    impl_to_ocaml_variant! {
+        impl<T, OT> Tree<T> => OCamlTree<OT>
+        where
+            T: ToOCaml<OT>,
+        {
+            Tree::Leaf,
+            Tree::Node(left: OCamlTree<OT>, value: OT, right: OCamlTree<OT>),
+        }
+    }
+
+    impl_conv_ocaml_record! {
+        Tally {
+            #[setter(set_label)]
+            label: String,
+            #[setter(set_total)]
+            total: OCamlInt,
+        }
+    }
+
+    impl_from_ocaml_record! {
+        OCamlMessage => MessageRef<'a> {
+            topic: String,
+            body: OCamlBytes,
+            priority: OCamlInt,
+            tags: OCamlList<String>,
+        }
+    }
+
+    impl_from_ocaml_record! {
+        Settings {
+            name: String,
+            #[force]
+            limits: OCamlLazy<(OCamlInt, OCamlInt)>,
+        }
+    }
+
+    impl_from_ocaml_record! {
+        Point {
+            #[setter(set_x)]
+            x: OCamlFloat,
+            #[setter(set_y)]
+            y: OCamlFloat,
+        }
+    }
+
+    impl_to_ocaml_polymorphic_variant! {
+        PolymorphicMovement {
+            Step(count: OCamlInt) => PolymorphicMovement::Step(count),
+            RotateLeft => PolymorphicMovement::RotateLeft,
+            RotateRight => PolymorphicMovement::RotateRight,
+        }
+    }
+
     ocaml! {
+        #![check_registered(pub fn missing_functions)]
+
         pub fn increment_bytes(bytes: String, first_n: OCamlInt) -> String;
         pub fn increment_ints_list(ints: OCamlList<OCamlInt>) -> OCamlList<OCamlInt>;
         pub fn twice(num: OCamlInt) -> OCamlInt;
@@ -55,9 +284,45 @@ mod ocaml {
         pub fn make_error(value: String) -> Result<OCamlInt, String>;
         pub fn stringify_record(record: TestRecord) -> String;
         pub fn stringify_variant(variant: Movement) -> String;
+        pub fn stringify_polymorphic_variant(variant: PolymorphicMovement) -> String;
+        pub fn stringify_shape(shape: Shape) -> String;
+        pub fn make_rect(width: OCamlInt, height: OCamlInt) -> Shape;
+        pub fn stringify_term(term: Term) -> String;
+        pub fn make_pair_term(n: OCamlInt, b: bool) -> Term;
+        pub fn stringify_event(event: Event) -> String;
+        pub fn make_failed_event(code: OCamlInt, reason: String) -> Event;
+        pub fn make_plugin_event(unit: ()) -> PluginEvent;
+        pub fn register_plugin_event(unit: ());
+        pub fn stringify_plugin_event(event: PluginEvent) -> String;
+        pub fn make_plugin_error(message: String) -> PluginError;
+        pub fn next_user_id(id: UserId) -> UserId;
+        pub fn double_meters(meters: Meters) -> Meters;
+        pub fn make_page(items: OCamlList<OCamlInt>) -> OCamlPage<OCamlInt>;
+        pub fn sum_tree(tree: OCamlTree<OCamlInt>) -> OCamlInt;
+        pub fn lazy_length(s: String) -> OCamlLazy<OCamlInt>;
+        pub fn lazy_failure(message: String) -> OCamlLazy<OCamlInt>;
+        pub fn force_lazy_int(l: OCamlLazy<OCamlInt>) -> OCamlInt;
+        pub fn make_settings(name: String) -> Settings;
+        pub fn make_counter(initial: OCamlInt) -> OCamlObject;
+        pub fn counter_id(counter: OCamlObject) -> OCamlInt;
+        pub fn register_late_value(n: OCamlInt);
+        pub fn register_late_function(unit: ());
+        pub fn late_function(n: OCamlInt) -> OCamlInt;
+        pub fn lwt_double(promise: OCamlLwt<OCamlInt>) -> OCamlLwt<OCamlInt>;
+        pub fn apply_to_ten(f: OCamlFunction<OCamlInt, OCamlInt>) -> OCamlInt;
+        pub fn make_tally(label: String) -> Tally;
+        pub fn describe_tally(tally: Tally) -> String;
+        pub fn make_point(x: OCamlFloat, y: OCamlFloat) -> Point;
+        pub fn describe_point(point: Point) -> String;
+        pub fn make_message(topic: String, priority: OCamlInt) -> OCamlMessage;
+        pub fn make_int_ref(n: OCamlInt) -> OCamlRefCell<OCamlInt>;
+        pub fn deref_int(r: OCamlRefCell<OCamlInt>) -> OCamlInt;
         pub fn raises_message_exception(message: String);
         pub fn raises_nonmessage_exception(unit: ());
         pub fn raises_nonblock_exception(unit: ());
+        pub fn z_to_string(z: OCamlZ) -> String;
+        pub fn z_of_string(s: String) -> OCamlZ;
+        pub fn q_to_string(q: OCamlQ) -> String;
     }
 }
 
@@ -131,6 +396,17 @@ pub fn verify_variant_test(cr: &mut OCamlRuntime, variant: ocaml::Movement) -> S
     })
 }
 
+pub fn verify_polymorphic_variant_test(
+    cr: &mut OCamlRuntime,
+    variant: ocaml::PolymorphicMovement,
+) -> String {
+    ocaml_frame!(cr, (root), {
+        let ocaml_variant = to_ocaml!(cr, variant, root);
+        let result = ocaml::stringify_polymorphic_variant(cr, ocaml_variant);
+        result.to_rust()
+    })
+}
+
 pub fn allocate_alot(cr: &mut OCamlRuntime) -> bool {
     let vec = vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
     for _n in 1..50000 {
@@ -236,6 +512,70 @@ fn test_record_conversion() {
     assert_eq!(verify_record_test(&mut cr, record), expected);
 }
 
+#[test]
+#[serial]
+fn test_borrowed_record_conversion() {
+    OCamlRuntime::init_persistent();
+    let cr = unsafe { OCamlRuntime::recover_handle() };
+    ocaml_frame!(cr, (topic_root, message_root), {
+        let topic = to_ocaml!(cr, "news", topic_root);
+        let message = ocaml::make_message(cr, topic, &OCaml::of_i32(2));
+        let message = message_root.keep(message);
+
+        let view: ocaml::MessageRef = message.to_rust_borrowed(cr);
+        assert_eq!(view.topic, "news");
+        assert_eq!(view.body, b"NEWS");
+        assert_eq!(view.priority, 2);
+        assert_eq!(view.tags, vec!["breaking", "news"]);
+
+        let message = cr.get(message);
+        let view: ocaml::MessageRef = message.to_rust_borrowed();
+        assert_eq!(view.topic, view.tags[1]);
+        assert_eq!(view.body, b"NEWS");
+    });
+}
+
+#[test]
+#[serial]
+fn test_mutable_values() {
+    OCamlRuntime::init_persistent();
+    let cr = unsafe { OCamlRuntime::recover_handle() };
+    ocaml_frame!(cr, (t_root, l_root, x_root, y_root, p_root, r_root), {
+        let label = to_ocaml!(cr, "apples", l_root);
+        let tally = ocaml::make_tally(cr, label);
+        let tally = t_root.keep(tally);
+        ocaml::Tally::set_total(cr, tally, &OCaml::of_i32(3));
+        let label = to_ocaml!(cr, "pears", l_root);
+        ocaml::Tally::set_label(cr, tally, label);
+        cr.gc().minor();
+        let description: String = ocaml::describe_tally(cr, tally).to_rust();
+        assert_eq!(description, "pears=3");
+        let tally: ocaml::Tally = tally.to_rust(cr);
+        assert_eq!(
+            tally,
+            ocaml::Tally {
+                label: "pears".to_owned(),
+                total: 3
+            }
+        );
+
+        let x: OCamlRef<OCamlFloat> = to_ocaml!(cr, 1.0, x_root);
+        let y: OCamlRef<OCamlFloat> = to_ocaml!(cr, 2.0, y_root);
+        let point = ocaml::make_point(cr, x, y);
+        let point = p_root.keep(point);
+        let y: OCamlRef<OCamlFloat> = to_ocaml!(cr, 5.5, y_root);
+        ocaml::Point::set_y(cr, point, y);
+        let description: String = ocaml::describe_point(cr, point).to_rust();
+        assert_eq!(description, "(1.0, 5.5)");
+
+        let r = ocaml::make_int_ref(cr, &OCaml::of_i32(1));
+        let r = r_root.keep(r);
+        r.set(cr, &OCaml::of_i32(42));
+        let value: i64 = ocaml::deref_int(cr, r).to_rust();
+        assert_eq!(value, 42);
+    });
+}
+
 #[test]
 #[serial]
 fn test_variant_conversion() {
@@ -300,4 +640,952 @@ fn test_exception_handling_nonblock_exception() {
         result.err().and_then(|err| Some(err.downcast_ref::<String>().unwrap().clone())).unwrap(),
         "OCaml exception, message: None"
     );
-}
\ No newline at end of file
+}
+
+#[test]
+#[serial]
+fn test_runtime_builder_invalid_argument() {
+    let result = OCamlRuntime::builder()
+        .program_name("rust-caller")
+        .arg("invalid\0argument")
+        .build_persistent();
+    match result {
+        Err(OCamlRuntimeInitError::InvalidArgument(arg)) => assert_eq!(arg, "invalid\0argument"),
+        _ => panic!("expected an invalid argument error"),
+    }
+}
+
+#[test]
+#[serial]
+fn test_gc_control_and_stats() {
+    OCamlRuntime::init_persistent();
+    let cr = unsafe { OCamlRuntime::recover_handle() };
+    let mut gc = cr.gc();
+    let before = gc.quick_stat();
+    gc.full_major();
+    let after = gc.quick_stat();
+    assert!(after.major_collections > before.major_collections);
+
+    let mut control = gc.control();
+    control.space_overhead = 150;
+    gc.set_control(&control);
+    assert_eq!(gc.control().space_overhead, 150);
+}
+
+#[test]
+#[serial]
+fn test_gc_alarm_and_finaliser() {
+    use std::{cell::Cell, rc::Rc};
+
+    OCamlRuntime::init_persistent();
+    let cr = unsafe { OCamlRuntime::recover_handle() };
+    let alarm_calls = Rc::new(Cell::new(0));
+    let finalised = Rc::new(Cell::new(false));
+
+    let alarm_counter = alarm_calls.clone();
+    let alarm = cr
+        .gc()
+        .create_alarm(move |_cr| alarm_counter.set(alarm_counter.get() + 1));
+
+    let finalised_flag = finalised.clone();
+    ocaml_frame!(cr, (bytes_root), {
+        let bytes: OCamlRef<OCamlBytes> = to_ocaml!(cr, "finalise me", bytes_root);
+        bytes.finalise_last(cr, move |_cr| finalised_flag.set(true));
+    });
+
+    cr.gc().full_major();
+    cr.gc().full_major();
+    assert!(alarm_calls.get() > 0);
+    assert!(finalised.get());
+
+    alarm.delete();
+    let calls = alarm_calls.get();
+    cr.gc().full_major();
+    assert_eq!(alarm_calls.get(), calls);
+}
+
+#[test]
+#[serial]
+fn test_gc_external_memory() {
+    use std::{cell::Cell, rc::Rc};
+
+    struct DropCounter(Rc<Cell<usize>>);
+
+    impl Drop for DropCounter {
+        fn drop(&mut self) {
+            self.0.set(self.0.get() + 1);
+        }
+    }
+
+    OCamlRuntime::init_persistent();
+    let cr = unsafe { OCamlRuntime::recover_handle() };
+    let megabyte = 1024 * 1024;
+
+    // Accounting resources that add up to the maximum forces major GC work
+    cr.gc().full_major();
+    let before = cr.gc().quick_stat();
+    for _ in 0..100 {
+        cr.gc().adjust_speed(megabyte, megabyte);
+        let _: OCaml<String> = "allocate".to_ocaml(cr);
+    }
+    assert!(cr.gc().quick_stat().major_collections > before.major_collections);
+
+    // Boxed values with a size hint are collected without explicit GC calls
+    let drops = Rc::new(Cell::new(0));
+    let before = cr.gc().quick_stat();
+    for _ in 0..1000 {
+        OCaml::box_value_with_size(cr, DropCounter(drops.clone()), megabyte);
+    }
+    assert!(cr.gc().quick_stat().major_collections > before.major_collections);
+    assert!(drops.get() > 0);
+    cr.gc().full_major();
+    assert_eq!(drops.get(), 1000);
+
+    // Memory accounted for a value is released when it gets collected
+    let finalised = Rc::new(Cell::new(false));
+    let finalised_flag = finalised.clone();
+    ocaml_frame!(cr, (bytes_root), {
+        let bytes: OCamlRef<OCamlBytes> = to_ocaml!(cr, "external", bytes_root);
+        bytes.account_external_memory(cr, megabyte);
+        bytes.finalise_last(cr, move |_cr| finalised_flag.set(true));
+    });
+    cr.gc().full_major();
+    cr.gc().full_major();
+    assert!(finalised.get());
+}
+
+#[test]
+#[serial]
+fn test_weak_array() {
+    use ocaml_interop::OCamlWeak;
+
+    OCamlRuntime::init_persistent();
+    let cr = unsafe { OCamlRuntime::recover_handle() };
+    ocaml_frame!(cr, (weak_root, kept_root, value_root), {
+        let weak = OCaml::<OCamlWeak<String>>::create_weak(cr, 2);
+        let weak = weak_root.keep(weak);
+        let kept: OCamlRef<String> = to_ocaml!(cr, "kept", kept_root);
+        weak.set(cr, 0, kept);
+        {
+            let collected: OCamlRef<String> = to_ocaml!(cr, "collected", value_root);
+            weak.set(cr, 1, collected);
+        }
+        value_root.keep(OCaml::unit());
+        cr.gc().full_major();
+        assert!(weak.check(cr, 0));
+        assert!(!weak.check(cr, 1));
+        let value: Option<String> = weak.get(cr, 0).to_rust();
+        assert_eq!(value.as_deref(), Some("kept"));
+    });
+}
+
+#[test]
+#[serial]
+fn test_ephemeron() {
+    use ocaml_interop::OCamlEphemeron;
+
+    OCamlRuntime::init_persistent();
+    let cr = unsafe { OCamlRuntime::recover_handle() };
+    ocaml_frame!(cr, (ephe_root, key_root, data_root), {
+        let ephe = OCaml::<OCamlEphemeron<String, String>>::create_ephemeron(cr, 1);
+        assert_eq!(ephe.keys_len(), 1);
+        let ephe = ephe_root.keep(ephe);
+        assert!(!ephe.check_key(cr, 0));
+        assert!(!ephe.check_data(cr));
+
+        let key: OCamlRef<String> = to_ocaml!(cr, "key", key_root);
+        let data: OCamlRef<String> = to_ocaml!(cr, "data", data_root);
+        ephe.set_key(cr, 0, key);
+        ephe.set_data(cr, data);
+        data_root.keep(OCaml::unit());
+
+        // The data is kept alive by the ephemeron while the key is alive
+        cr.gc().full_major();
+        assert!(ephe.check_key(cr, 0));
+        assert!(ephe.check_data(cr));
+        let key: Option<String> = ephe.get_key(cr, 0).to_rust();
+        assert_eq!(key.as_deref(), Some("key"));
+        let data: Option<String> = ephe.get_data(cr).to_rust();
+        assert_eq!(data.as_deref(), Some("data"));
+
+        // Once the key is collected, the data is cleared
+        key_root.keep(OCaml::unit());
+        cr.gc().full_major();
+        assert!(!ephe.check_key(cr, 0));
+        assert!(!ephe.check_data(cr));
+        let key: Option<String> = ephe.get_key(cr, 0).to_rust();
+        assert_eq!(key, None);
+        let data: Option<String> = ephe.get_data(cr).to_rust();
+        assert_eq!(data, None);
+
+        let key: OCamlRef<String> = to_ocaml!(cr, "other key", key_root);
+        let data: OCamlRef<String> = to_ocaml!(cr, "other data", data_root);
+        ephe.set_key(cr, 0, key);
+        ephe.set_data(cr, data);
+        ephe.unset_data(cr);
+        assert!(!ephe.check_data(cr));
+        ephe.unset_key(cr, 0);
+        assert!(!ephe.check_key(cr, 0));
+    });
+}
+
+#[test]
+#[serial]
+fn test_integer_conversions() {
+    use ocaml_interop::{OCamlChar, OCamlInt, OCamlNativeint, OCamlUchar, TryToOCaml};
+    use std::num::{Saturating, Wrapping};
+
+    OCamlRuntime::init_persistent();
+    let cr = unsafe { OCamlRuntime::recover_handle() };
+
+    let n: OCaml<OCamlInt> = 42u64.to_ocaml(cr);
+    assert_eq!(n.to_rust::<u64>(), 42);
+    let n: OCaml<OCamlInt> = Saturating(u64::MAX).to_ocaml(cr);
+    assert_eq!(n.to_rust::<i64>(), i64::MAX >> 1);
+    let n: OCaml<OCamlInt> = Wrapping(i64::MAX).to_ocaml(cr);
+    assert_eq!(n.to_rust::<i64>(), -1);
+    let n: OCaml<OCamlInt> = (-3i64).to_ocaml(cr);
+    assert_eq!(n.to_rust::<Saturating<u8>>().0, 0);
+    assert!(OCaml::<OCamlInt>::of_i128(i128::MAX).is_err());
+    let n: Result<OCaml<OCamlInt>, _> = u64::MAX.try_to_ocaml(cr);
+    assert!(n.is_err());
+    let n: OCaml<OCamlInt> = (-5i8).try_to_ocaml(cr).unwrap();
+    assert_eq!(n.to_rust::<i64>(), -5);
+
+    let n: OCaml<OCamlNativeint> = (-7isize).to_ocaml(cr);
+    assert_eq!(n.to_rust::<isize>(), -7);
+    let c: OCaml<OCamlChar> = 'é'.to_ocaml(cr);
+    assert_eq!(c.to_rust::<char>(), 'é');
+    let c: Result<OCaml<OCamlChar>, _> = 'λ'.try_to_ocaml(cr);
+    assert!(c.is_err());
+    let c: OCaml<OCamlUchar> = 'λ'.to_ocaml(cr);
+    assert_eq!(c.to_rust::<char>(), 'λ');
+}
+
+#[test]
+#[serial]
+fn test_zarith_conversions() {
+    use num_bigint::BigInt;
+    use num_rational::BigRational;
+
+    OCamlRuntime::init_persistent();
+    let cr = unsafe { OCamlRuntime::recover_handle() };
+    let big = BigInt::from(-3) * BigInt::from(2).pow(100u32);
+    let small = BigInt::from(42);
+    let q = BigRational::new(big.clone(), BigInt::from(7));
+    ocaml_frame!(cr, (big_root, small_root, s_root, q_root), {
+        let z = to_ocaml!(cr, big, big_root);
+        let s: String = ocaml::z_to_string(cr, z).to_rust();
+        assert_eq!(s, big.to_string());
+        let z = to_ocaml!(cr, small, small_root);
+        let s: String = ocaml::z_to_string(cr, z).to_rust();
+        assert_eq!(s, "42");
+        let s = to_ocaml!(cr, big.to_string(), s_root);
+        let z: BigInt = ocaml::z_of_string(cr, s).to_rust();
+        assert_eq!(z, big);
+        let q = to_ocaml!(cr, q, q_root);
+        let s: String = ocaml::q_to_string(cr, q).to_rust();
+        assert_eq!(s, format!("{}/7", big));
+    });
+}
+
+#[test]
+#[serial]
+fn test_collection_conversions() {
+    use ocaml_interop::{OCamlHashtbl, OCamlInt, OCamlList, OCamlMap, OCamlSet};
+    use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+    OCamlRuntime::init_persistent();
+    let cr = unsafe { OCamlRuntime::recover_handle() };
+    let mut map = HashMap::new();
+    map.insert("one".to_owned(), 1);
+    map.insert("two".to_owned(), 2);
+    map.insert("three".to_owned(), 3);
+    let set: BTreeSet<String> = map.keys().cloned().collect();
+
+    let table: OCaml<OCamlHashtbl<String, OCamlInt>> = map.to_ocaml(cr);
+    assert_eq!(table.len(), 3);
+    let from_table: HashMap<String, i64> = table.to_rust();
+    assert_eq!(from_table, map);
+
+    ocaml_frame!(cr, (bindings_root, elements_root), {
+        let bindings: OCamlRef<OCamlList<(String, OCamlInt)>> = to_ocaml!(cr, map, bindings_root);
+        let ocaml_map = OCamlMap::of_list(cr, "string_map_of_list", bindings);
+        let keys: Vec<String> = ocaml_map.iter().map(|(k, _)| k.to_rust()).collect();
+        assert_eq!(keys, vec!["one", "three", "two"]);
+        let from_map: BTreeMap<String, i64> = ocaml_map.to_rust();
+        assert_eq!(from_map.len(), 3);
+        assert_eq!(from_map["two"], 2);
+
+        let elements: OCamlRef<OCamlList<String>> = to_ocaml!(cr, set, elements_root);
+        let ocaml_set: OCaml<OCamlSet<String>> =
+            OCamlSet::of_list(cr, "string_set_of_list", elements);
+        let from_set: BTreeSet<String> = ocaml_set.to_rust();
+        assert_eq!(from_set, set);
+    });
+}
+
+#[test]
+#[serial]
+fn test_large_tuples() {
+    use ocaml_interop::{OCamlFloat, OCamlInt};
+
+    OCamlRuntime::init_persistent();
+    let cr = unsafe { OCamlRuntime::recover_handle() };
+    let tuple = (
+        1,
+        2.5,
+        "three".to_owned(),
+        4,
+        5,
+        6,
+        true,
+        8,
+        9,
+        10,
+        11,
+        "twelve".to_owned(),
+    );
+    let ocaml_tuple: OCaml<(
+        OCamlInt,
+        OCamlFloat,
+        String,
+        OCamlInt,
+        OCamlInt,
+        OCamlInt,
+        bool,
+        OCamlInt,
+        OCamlInt,
+        OCamlInt,
+        OCamlInt,
+        String,
+    )> = tuple.to_ocaml(cr);
+    assert_eq!(ocaml_tuple.element::<2>().to_rust::<String>(), "three");
+    assert_eq!(ocaml_tuple.tuple_12().to_rust::<String>(), "twelve");
+    assert!(ocaml_tuple.element::<6>().to_bool());
+    let back: (
+        i64,
+        f64,
+        String,
+        i64,
+        i64,
+        i64,
+        bool,
+        i64,
+        i64,
+        i64,
+        i64,
+        String,
+    ) = ocaml_tuple.to_rust();
+    assert_eq!(back, tuple);
+
+    // Arrays convert like tuples with elements of the same type
+    let array = [1.5, 2.5, 3.5];
+    let ocaml_array: OCaml<(OCamlFloat, OCamlFloat, OCamlFloat)> = array.to_ocaml(cr);
+    assert_eq!(ocaml_array.element::<2>().to_rust::<f64>(), 3.5);
+    let back: [f64; 3] = ocaml_array.to_rust();
+    assert_eq!(back, array);
+
+    let words = ["one", "two"];
+    let ocaml_words: OCaml<(String, String)> = words.to_ocaml(cr);
+    let back: (String, String) = ocaml_words.to_rust();
+    assert_eq!(back, ("one".to_owned(), "two".to_owned()));
+    let borrowed: [&str; 2] = ocaml_words.to_rust_borrowed();
+    assert_eq!(borrowed, words);
+}
+
+#[test]
+#[serial]
+fn test_long_lists() {
+    use ocaml_interop::{OCamlInt, OCamlList, OCamlListIter};
+
+    OCamlRuntime::init_persistent();
+    let cr = unsafe { OCamlRuntime::recover_handle() };
+    let len = 1_000_000;
+    let list: OCaml<OCamlList<OCamlInt>> = OCaml::of_iter(cr, 0..len);
+    let iter: OCamlListIter<OCamlInt> = list.iter();
+    assert_eq!(iter.count(), len as usize);
+    assert_eq!(
+        list.iter().map(|n| n.to_i64()).sum::<i64>(),
+        len * (len - 1) / 2
+    );
+    let vec: Vec<i64> = list.to_rust();
+    assert_eq!(vec.len(), len as usize);
+    assert_eq!(vec[1234], 1234);
+    let list: OCaml<OCamlList<OCamlInt>> = vec.to_ocaml(cr);
+    assert_eq!(list.hd().map(|n| n.to_i64()), Some(0));
+}
+
+#[test]
+#[serial]
+fn test_polymorphic_variant_conversion() {
+    OCamlRuntime::init_persistent();
+    let mut cr = unsafe { OCamlRuntime::recover_handle() };
+    assert_eq!(
+        verify_polymorphic_variant_test(&mut cr, ocaml::PolymorphicMovement::RotateLeft),
+        "`RotateLeft".to_owned()
+    );
+    assert_eq!(
+        verify_polymorphic_variant_test(&mut cr, ocaml::PolymorphicMovement::RotateRight),
+        "`RotateRight".to_owned()
+    );
+    assert_eq!(
+        verify_polymorphic_variant_test(&mut cr, ocaml::PolymorphicMovement::Step(10)),
+        "`Step(10)".to_owned()
+    );
+}
+
+#[test]
+#[serial]
+fn test_inline_record_variant_conversion() {
+    OCamlRuntime::init_persistent();
+    let mut cr = unsafe { OCamlRuntime::recover_handle() };
+    let cr = &mut cr;
+    let shapes = vec![
+        (ocaml::Shape::Point, "Point"),
+        (ocaml::Shape::Circle(1.5), "Circle(1.5)"),
+        (
+            ocaml::Shape::Rect {
+                height: 2,
+                width: 3,
+            },
+            "Rect { width = 3; height = 2 }",
+        ),
+    ];
+    for (shape, expected) in shapes {
+        let description: String = ocaml_frame!(cr, (root), {
+            let shape = to_ocaml!(cr, shape, root);
+            ocaml::stringify_shape(cr, shape).to_rust()
+        });
+        assert_eq!(description, expected);
+    }
+
+    let width = OCaml::of_i32(3);
+    let height = OCaml::of_i32(2);
+    let rect: ocaml::Shape = ocaml::make_rect(cr, &width, &height).to_rust();
+    assert_eq!(
+        rect,
+        ocaml::Shape::Rect {
+            height: 2,
+            width: 3
+        }
+    );
+}
+
+#[test]
+#[serial]
+fn test_gadt_variant_conversion() {
+    use ocaml::Term;
+
+    OCamlRuntime::init_persistent();
+    let cr = unsafe { OCamlRuntime::recover_handle() };
+    let term = Term::If {
+        cond: Box::new(Term::Bool(true)),
+        then_: Box::new(Term::Int(1)),
+        else_: Box::new(Term::Int(2)),
+    };
+    let term = Term::Pair(Box::new(term), Box::new(Term::Bool(false)));
+    let description: String = ocaml_frame!(cr, (root), {
+        let term = to_ocaml!(cr, term, root);
+        ocaml::stringify_term(cr, term).to_rust()
+    });
+    assert_eq!(description, "(if true then 1 else 2, false)");
+
+    let term: Term = ocaml::make_pair_term(cr, &OCaml::of_i32(7), &OCaml::of_bool(true)).to_rust();
+    let expected = Term::Pair(
+        Box::new(Term::Int(7)),
+        Box::new(Term::If {
+            cond: Box::new(Term::Bool(true)),
+            then_: Box::new(Term::Bool(false)),
+            else_: Box::new(Term::Bool(true)),
+        }),
+    );
+    assert_eq!(term, expected);
+}
+
+#[test]
+#[serial]
+fn test_extensible_variant_conversion() {
+    OCamlRuntime::init_persistent();
+    let mut cr = unsafe { OCamlRuntime::recover_handle() };
+    let cr = &mut cr;
+    let events = vec![
+        (ocaml::Event::Started, "Started"),
+        (ocaml::Event::Progress(50), "Progress(50)"),
+        (
+            ocaml::Event::Failed {
+                code: 2,
+                reason: "timeout".to_owned(),
+            },
+            "Failed { code = 2; reason = timeout }",
+        ),
+    ];
+    for (event, expected) in events {
+        let description: String = ocaml_frame!(cr, (root), {
+            let event = to_ocaml!(cr, event, root);
+            ocaml::stringify_event(cr, event).to_rust()
+        });
+        assert_eq!(description, expected);
+    }
+
+    let event: ocaml::Event = ocaml_frame!(cr, (reason), {
+        let code = OCaml::of_i32(3);
+        let reason = to_ocaml!(cr, "crash", reason);
+        ocaml::make_failed_event(cr, &code, reason).to_rust()
+    });
+    assert_eq!(
+        event,
+        ocaml::Event::Failed {
+            code: 3,
+            reason: "crash".to_owned()
+        }
+    );
+
+    let error: ocaml::PluginError = ocaml_frame!(cr, (message), {
+        let message = to_ocaml!(cr, "missing plugin", message);
+        ocaml::make_plugin_error(cr, message).to_rust()
+    });
+    assert_eq!(
+        error,
+        ocaml::PluginError::Error("missing plugin".to_owned())
+    );
+}
+
+#[test]
+#[serial]
+fn test_late_extension_constructor_registration() {
+    OCamlRuntime::init_persistent();
+    let mut cr = unsafe { OCamlRuntime::recover_handle() };
+    let cr = &mut cr;
+    let event = ocaml::make_plugin_event(cr, &OCaml::unit());
+    let result = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+        event.to_rust::<ocaml::PluginEvent>()
+    }));
+    assert!(result.is_err());
+
+    ocaml::register_plugin_event(cr, &OCaml::unit());
+    let event: ocaml::PluginEvent = ocaml::make_plugin_event(cr, &OCaml::unit()).to_rust();
+    assert_eq!(event, ocaml::PluginEvent::Loaded("metrics".to_owned()));
+    let description: String = ocaml_frame!(cr, (root), {
+        let event = to_ocaml!(cr, event, root);
+        ocaml::stringify_plugin_event(cr, event).to_rust()
+    });
+    assert_eq!(description, "Plugin_loaded(metrics)");
+}
+
+#[test]
+#[serial]
+fn test_unboxed_conversion() {
+    OCamlRuntime::init_persistent();
+    let mut cr = unsafe { OCamlRuntime::recover_handle() };
+    let cr = &mut cr;
+    let id: ocaml::UserId = ocaml_frame!(cr, (root), {
+        let id = to_ocaml!(cr, ocaml::UserId(41), root);
+        ocaml::next_user_id(cr, id).to_rust()
+    });
+    assert_eq!(id, ocaml::UserId(42));
+
+    let meters: ocaml::Meters = ocaml_frame!(cr, (root), {
+        let meters = to_ocaml!(cr, ocaml::Meters::Meters(1.5), root);
+        ocaml::double_meters(cr, meters).to_rust()
+    });
+    assert_eq!(meters, ocaml::Meters::Meters(3.0));
+}
+
+#[test]
+#[serial]
+fn test_generic_conversion() {
+    OCamlRuntime::init_persistent();
+    let mut cr = unsafe { OCamlRuntime::recover_handle() };
+    let cr = &mut cr;
+    let page: ocaml::Page<i64> = ocaml_frame!(cr, (root), {
+        let items = to_ocaml!(cr, vec![1, 2, 3], root);
+        ocaml::make_page(cr, items).to_rust()
+    });
+    assert_eq!(
+        page,
+        ocaml::Page {
+            items: vec![1, 2, 3],
+            next: Some("3".to_owned()),
+        }
+    );
+
+    let leaf = || Box::new(ocaml::Tree::Leaf);
+    let tree = ocaml::Tree::Node(
+        Box::new(ocaml::Tree::Node(leaf(), 1, leaf())),
+        2,
+        Box::new(ocaml::Tree::Node(leaf(), 3, leaf())),
+    );
+    let sum: i64 = ocaml_frame!(cr, (root), {
+        let tree = to_ocaml!(cr, tree, root);
+        ocaml::sum_tree(cr, tree).to_rust()
+    });
+    assert_eq!(sum, 6);
+}
+
+#[test]
+#[serial]
+fn test_lazy_values() {
+    OCamlRuntime::init_persistent();
+    let mut cr = unsafe { OCamlRuntime::recover_handle() };
+    let cr = &mut cr;
+    ocaml_frame!(cr, (s, l), {
+        let s = to_ocaml!(cr, "lazy", s);
+        let l_value = ocaml::lazy_length(cr, s);
+        assert!(!l_value.is_forced());
+        let l = l.keep(l_value);
+        let length: i64 = l.force(cr).unwrap().to_rust();
+        assert_eq!(length, 4);
+        assert!(l.is_forced());
+        let length: i64 = l.to_rust_forced(cr).unwrap();
+        assert_eq!(length, 4);
+    });
+
+    ocaml_frame!(cr, (message, l), {
+        let message = to_ocaml!(cr, "lazy failure", message);
+        let l_value = ocaml::lazy_failure(cr, message);
+        let l = l.keep(l_value);
+        let error = l.force(cr).err().unwrap();
+        assert_eq!(error.message(), Some("lazy failure".to_owned()));
+    });
+
+    ocaml_frame!(cr, (l), {
+        let l_value = OCamlLazy::<OCamlInt>::of_rust_closure(cr, |_cr| OCaml::of_i32(42));
+        let l = l.keep(l_value);
+        let value: i64 = ocaml::force_lazy_int(cr, l).to_rust();
+        assert_eq!(value, 42);
+    });
+}
+
+#[test]
+#[serial]
+fn test_forced_lazy_conversion() {
+    OCamlRuntime::init_persistent();
+    let mut cr = unsafe { OCamlRuntime::recover_handle() };
+    let cr = &mut cr;
+    ocaml_frame!(cr, (s, l), {
+        let s = to_ocaml!(cr, "unforced", s);
+        let l = l.keep(ocaml::lazy_length(cr, s));
+        assert!(!l.is_forced());
+        let length: Result<i64, _> = l.to_rust_forced(cr);
+        assert_eq!(length.unwrap(), 8);
+        assert!(l.is_forced());
+    });
+
+    ocaml_frame!(cr, (message, l), {
+        let message = to_ocaml!(cr, "unforced failure", message);
+        let l = l.keep(ocaml::lazy_failure(cr, message));
+        let error = l.to_rust_forced::<i64>(cr).unwrap_err();
+        assert_eq!(error.message(), Some("unforced failure".to_owned()));
+    });
+
+    ocaml_frame!(cr, (name, settings), {
+        let name = to_ocaml!(cr, "settings", name);
+        let settings = settings.keep(ocaml::make_settings(cr, name));
+        let settings = ocaml::Settings::to_rust_forced(cr, settings).unwrap();
+        let expected = ocaml::Settings {
+            name: "settings".to_owned(),
+            limits: (0, 8),
+        };
+        assert_eq!(settings, expected);
+    });
+}
+
+#[test]
+#[serial]
+fn test_object_methods() {
+    OCamlRuntime::init_persistent();
+    let mut cr = unsafe { OCamlRuntime::recover_handle() };
+    let cr = &mut cr;
+    ocaml_frame!(cr, (counter, prefix, suffix), {
+        let counter_value = ocaml::make_counter(cr, &OCaml::of_i32(10));
+        assert!(counter_value.has_method("add"));
+        assert!(!counter_value.has_method("sub"));
+        let id = counter_value.id();
+        let counter = counter.keep(counter_value);
+        let expected_id: i64 = ocaml::counter_id(cr, counter).to_rust();
+        assert_eq!(id, expected_id);
+
+        let count: i64 = counter.call_method::<OCamlInt>(cr, "get").to_rust();
+        assert_eq!(count, 10);
+        let count: i64 = counter
+            .call_method1::<OCamlInt, OCamlInt>(cr, "add", &OCaml::of_i32(5))
+            .to_rust();
+        assert_eq!(count, 15);
+
+        let prefix = to_ocaml!(cr, "count: ", prefix);
+        let suffix = to_ocaml!(cr, "!", suffix);
+        let description: String = counter
+            .call_method2::<String, String, String>(cr, "describe", prefix, suffix)
+            .to_rust();
+        assert_eq!(description, "count: 15!");
+
+        let sum: i64 = counter
+            .call_method3::<OCamlInt, OCamlInt, OCamlInt, OCamlInt>(
+                cr,
+                "add3",
+                &OCaml::of_i32(1),
+                &OCaml::of_i32(2),
+                &OCaml::of_i32(3),
+            )
+            .to_rust();
+        assert_eq!(sum, 21);
+        let mut args = [1, 2, 3, 4].map(|n| unsafe { OCaml::of_i32(n).raw() });
+        let sum: i64 = counter
+            .call_method_n::<OCamlInt>(cr, "add4", &mut args)
+            .to_rust();
+        assert_eq!(sum, 25);
+    });
+}
+
+#[test]
+#[serial]
+fn test_polymorphic_comparison() {
+    OCamlRuntime::init_persistent();
+    let mut cr = unsafe { OCamlRuntime::recover_handle() };
+    let cr = &mut cr;
+    ocaml_frame!(cr, (a, b, c, f, g), {
+        let a: OCamlRef<(String, OCamlInt)> = to_ocaml!(cr, ("apple".to_owned(), 2i64), a);
+        let b: OCamlRef<(String, OCamlInt)> = to_ocaml!(cr, ("apple".to_owned(), 10i64), b);
+        let c: OCamlRef<(String, OCamlInt)> = to_ocaml!(cr, ("apple".to_owned(), 2i64), c);
+        assert_eq!(a.compare(cr, b).unwrap(), Ordering::Less);
+        assert_eq!(b.compare(cr, a).unwrap(), Ordering::Greater);
+        assert_eq!(a.compare(cr, c).unwrap(), Ordering::Equal);
+        assert!(a.equal(cr, c).unwrap());
+        assert!(!a.equal(cr, b).unwrap());
+        assert_eq!(a.hash(cr), c.hash(cr));
+
+        let f_value = OCamlLazy::<OCamlInt>::of_rust_closure(cr, |_cr| OCaml::of_i32(1));
+        let f = f.keep(f_value);
+        let g_value = OCamlLazy::<OCamlInt>::of_rust_closure(cr, |_cr| OCaml::of_i32(1));
+        let g = g.keep(g_value);
+        let error = f.compare(cr, g).unwrap_err();
+        assert_eq!(
+            error.message().as_deref(),
+            Some("compare: functional value")
+        );
+        assert!(f.equal(cr, g).is_err());
+        assert_eq!(f.compare(cr, f).unwrap(), Ordering::Equal);
+    });
+
+    let keys: Vec<OCamlKey<(String, OCamlInt)>> = vec![("pear", 1i64), ("apple", 3), ("pear", 1)]
+        .into_iter()
+        .map(|(name, n)| OCamlKey::new((name.to_owned(), n).to_ocaml(cr)).unwrap())
+        .collect();
+    let sorted: BTreeSet<&OCamlKey<(String, OCamlInt)>> = keys.iter().collect();
+    let sorted: Vec<(String, i64)> = sorted.into_iter().map(|k| k.get(cr).to_rust()).collect();
+    assert_eq!(
+        sorted,
+        vec![("apple".to_owned(), 3), ("pear".to_owned(), 1)]
+    );
+    let unique: HashSet<&OCamlKey<(String, OCamlInt)>> = keys.iter().collect();
+    assert_eq!(unique.len(), 2);
+
+    let lazy_value = OCamlLazy::<OCamlInt>::of_rust_closure(cr, |_cr| OCaml::of_i32(1));
+    let error = OCamlKey::new(lazy_value).err().unwrap();
+    assert_eq!(
+        error.message().as_deref(),
+        Some("compare: functional value")
+    );
+}
+
+static PRIMES: OCamlNamedValue<OCamlList<OCamlInt>> = OCamlNamedValue::new("primes");
+static LATE_VALUE: OCamlNamedValue<OCamlInt> = OCamlNamedValue::new("late_value");
+static MISSING_VALUE: OCamlNamedValue<OCamlInt> = OCamlNamedValue::new("missing_value");
+
+#[test]
+#[serial]
+fn test_named_values() {
+    OCamlRuntime::init_persistent();
+    let mut cr = unsafe { OCamlRuntime::recover_handle() };
+    let cr = &mut cr;
+    let primes: Vec<i64> = PRIMES.get(cr).unwrap().to_rust(cr);
+    assert_eq!(primes, vec![2, 3, 5, 7]);
+    assert_eq!(PRIMES.name(), "primes");
+    let primes = PRIMES.get(cr).unwrap();
+    let page: ocaml::Page<i64> = ocaml::make_page(cr, primes).to_rust();
+    assert_eq!(page.items, vec![2, 3, 5, 7]);
+    assert_eq!(page.next, Some("4".to_owned()));
+    assert!(MISSING_VALUE.get(cr).is_none());
+    assert!(MISSING_VALUE.refresh(cr).is_none());
+
+    assert!(LATE_VALUE.get(cr).is_none());
+    ocaml::register_late_value(cr, &OCaml::of_i32(1));
+    // The missing lookup is cached until refreshed
+    assert!(LATE_VALUE.get(cr).is_none());
+    let value: i64 = LATE_VALUE.refresh(cr).unwrap().to_rust(cr);
+    assert_eq!(value, 1);
+    ocaml::register_late_value(cr, &OCaml::of_i32(2));
+    let value: i64 = LATE_VALUE.get(cr).unwrap().to_rust(cr);
+    assert_eq!(value, 2);
+    let late_value = LATE_VALUE.get(cr).unwrap();
+    let value: i64 = ocaml::twice(cr, late_value).to_rust();
+    assert_eq!(value, 4);
+}
+
+#[test]
+#[serial]
+fn test_function_registration_checks() {
+    OCamlRuntime::init_persistent();
+    let mut cr = unsafe { OCamlRuntime::recover_handle() };
+    let cr = &mut cr;
+    assert!(ocaml::twice::is_available());
+    assert!(!ocaml::late_function::is_available());
+    let error = ocaml::late_function::try_resolve().unwrap_err();
+    assert_eq!(error.name(), "late_function");
+    assert_eq!(ocaml::missing_functions(), vec!["late_function"]);
+    let unregistered = panic::catch_unwind(|| {
+        let cr = unsafe { OCamlRuntime::recover_handle() };
+        ocaml::late_function(cr, &OCaml::of_i32(7));
+    });
+    let message = unregistered.unwrap_err().downcast::<String>().unwrap();
+    assert!(message.contains("late_function"));
+
+    ocaml::register_late_function(cr, &OCaml::unit());
+    assert!(ocaml::late_function::try_resolve().is_ok());
+    assert!(ocaml::missing_functions().is_empty());
+    let result: i64 = ocaml::late_function(cr, &OCaml::of_i32(7)).to_rust();
+    assert_eq!(result, 21);
+}
+
+#[test]
+#[serial]
+fn test_runtime_thread_calls() {
+    // Keeps the runtime initialized when the runtime thread shuts it down
+    OCamlRuntime::init_persistent();
+    let cr = unsafe { OCamlRuntime::recover_handle() };
+    let runtime = OCamlRuntimeThread::spawn();
+    let threads: Vec<_> = (0..4)
+        .map(|n| {
+            let handle = runtime.handle();
+            thread::spawn(move || handle.call(move |cr| twice(cr, n)))
+        })
+        .collect();
+    let results: Vec<i64> = threads.into_iter().map(|t| t.join().unwrap()).collect();
+    assert_eq!(results, vec![0, 2, 4, 6]);
+
+    let handle = runtime.handle();
+    let nested = panic::catch_unwind(|| {
+        let inner = handle.clone();
+        handle.call(move |_cr| inner.call(|_cr| ()))
+    });
+    assert!(nested.is_err());
+
+    let result = cr.releasing_runtime_await(runtime.handle().call_async(|cr| twice(cr, 21)));
+    assert_eq!(result, 42);
+}
+
+#[test]
+#[serial]
+fn test_releasing_runtime_await() {
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::sync::{Arc, Mutex};
+    use std::task::{Context, Poll, Waker};
+    use std::time::Duration;
+
+    // Resolved from another thread, after the runtime has been released
+    struct Delayed(Arc<Mutex<(Option<i64>, Option<Waker>)>>);
+
+    impl Future for Delayed {
+        type Output = i64;
+
+        fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<i64> {
+            let mut state = self.0.lock().unwrap();
+            match state.0 {
+                Some(value) => Poll::Ready(value),
+                None => {
+                    state.1 = Some(cx.waker().clone());
+                    Poll::Pending
+                }
+            }
+        }
+    }
+
+    OCamlRuntime::init_persistent();
+    let cr = unsafe { OCamlRuntime::recover_handle() };
+    let state = Arc::new(Mutex::new((None, None::<Waker>)));
+    let resolver_state = state.clone();
+    let resolver = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(50));
+        let mut state = resolver_state.lock().unwrap();
+        state.0 = Some(11);
+        if let Some(waker) = state.1.take() {
+            waker.wake();
+        }
+    });
+    let value = cr.releasing_runtime_await(Delayed(state));
+    resolver.join().unwrap();
+    assert_eq!(value, 11);
+    assert_eq!(twice(cr, value), 22);
+}
+
+#[test]
+#[serial]
+fn test_lwt_promises() {
+    OCamlRuntime::init_persistent();
+    let cr = unsafe { OCamlRuntime::recover_handle() };
+    ocaml_frame!(cr, (promise, doubled, rejected, abandoned), {
+        let (promise_value, resolver) = OCamlLwt::<OCamlInt>::pending(cr);
+        let promise = promise.keep(promise_value);
+        let doubled_value = ocaml::lwt_double(cr, promise);
+        let doubled = doubled.keep(doubled_value);
+        let future = doubled.to_future::<i64>(cr);
+        let resolver = thread::spawn(move || resolver).join().unwrap();
+        resolver.resolve(cr, &21i64);
+        assert_eq!(cr.releasing_runtime_await(future).unwrap(), 42);
+
+        let (rejected_value, resolver) = OCamlLwt::<OCamlInt>::pending(cr);
+        let rejected = rejected.keep(rejected_value);
+        let future = rejected.to_future::<i64>(cr);
+        resolver.reject(cr, "boom");
+        let error = cr.releasing_runtime_await(future).unwrap_err();
+        assert_eq!(error.message(), Some("boom"));
+
+        let (abandoned_value, resolver) = OCamlLwt::<OCamlInt>::pending(cr);
+        let abandoned = abandoned.keep(abandoned_value);
+        let future = abandoned.to_future::<i64>(cr);
+        drop(resolver);
+        cr.reject_abandoned_lwt_resolvers();
+        let error = cr.releasing_runtime_await(future).unwrap_err();
+        assert_eq!(
+            error.message(),
+            Some("Lwt resolver dropped before resolving the promise")
+        );
+    });
+}
+
+#[test]
+#[serial]
+fn test_rust_closure_called_from_ocaml() {
+    use std::sync::atomic::{AtomicBool, Ordering as AtomicOrdering};
+    use std::sync::Arc;
+
+    struct DropFlag(Arc<AtomicBool>);
+
+    impl Drop for DropFlag {
+        fn drop(&mut self) {
+            self.0.store(true, AtomicOrdering::SeqCst);
+        }
+    }
+
+    OCamlRuntime::init_persistent();
+    let cr = unsafe { OCamlRuntime::recover_handle() };
+    let dropped = Arc::new(AtomicBool::new(false));
+    ocaml_frame!(cr, (f), {
+        let flag = DropFlag(dropped.clone());
+        let f_value =
+            OCaml::<OCamlFunction<OCamlInt, OCamlInt>>::of_rust_closure(cr, move |cr, n| {
+                let _ = &flag;
+                let n: i64 = n.to_rust(cr);
+                OCaml::of_i64(n * 3 + 1).unwrap()
+            });
+        let f = f.keep(f_value);
+        let result: i64 = ocaml::apply_to_ten(cr, f).to_rust();
+        assert_eq!(result, 31);
+        let result: i64 = f.call(cr, &OCaml::of_i32(2)).to_rust();
+        assert_eq!(result, 7);
+    });
+    cr.gc().full_major();
+    assert!(dropped.load(AtomicOrdering::SeqCst));
+}
@@ -0,0 +1,30 @@
+// Copyright (c) SimpleStaking and Tezedge Contributors
+// SPDX-License-Identifier: MIT
+
+// Runs in its own process, because the configuration of the builder only
+// applies to the first initialization of the OCaml runtime.
+
+extern crate rust_caller;
+
+use ocaml_interop::{ocaml, OCaml, OCamlList, OCamlRuntime};
+
+ocaml! {
+    fn sys_argv(unit: ()) -> OCamlList<String>;
+}
+
+#[test]
+fn test_runtime_builder() {
+    let mut cr = OCamlRuntime::builder()
+        .program_name("rust-caller")
+        .args(&["--first", "second"])
+        .minor_heap_size(512 * 1024)
+        .space_overhead(150)
+        .build()
+        .unwrap();
+    let argv: Vec<String> = sys_argv(&mut cr, &OCaml::unit()).to_rust();
+    assert_eq!(argv, vec!["rust-caller", "--first", "second"]);
+
+    let control = cr.gc().control();
+    assert_eq!(control.minor_heap_size, 512 * 1024);
+    assert_eq!(control.space_overhead, 150);
+}